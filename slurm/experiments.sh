@@ -2,8 +2,15 @@
 
 export dim=$1
 
+# each array task runs two functions in one job queue on as many threads as it has CPUs; a task stopped by the
+# time limit can simply be submitted again, as completed runs are skipped
+
 sbatch slurm/pso.sbatch
 sbatch slurm/shade.sbatch
+sbatch slurm/lshade.sbatch
+sbatch slurm/jso.sbatch
+sbatch slurm/ipop_cma_es.sbatch
+sbatch slurm/bipop_cma_es.sbatch
 sbatch slurm/pso_rr.sbatch
 sbatch slurm/pso_gpgm.sbatch
 sbatch slurm/pso_npgm.sbatch
@@ -0,0 +1,140 @@
+use better_any::{Tid, TidAble};
+use mahf::{prelude::*, state::StateReq, CustomState, SingleObjective,
+           problems::{KnownOptimumProblem, LimitedVectorProblem, SingleObjectiveProblem}};
+use serde::Serialize;
+
+use super::exploration_pso::{BaseAlgorithm, ExplorationMechanism};
+use super::parameters::Boundary;
+
+/// Current number of solutions generated and replaced by the exploration mechanism, and the iteration and best
+/// objective value at its last activation that has not been rated yet.
+#[derive(Tid)]
+pub struct NewPop {
+    pub new_pop: u32,
+    last_activation: Option<(u32, Option<SingleObjective>)>,
+}
+
+impl CustomState<'_> for NewPop {}
+
+/// Executes the exploration mechanism with the current number of new solutions `new_pop` read from the state.
+///
+/// The mechanism is built for each activation with the current `new_pop`. Its state does not depend on the number
+/// of new solutions, so it is initialised once by the mechanism with the initial `new_pop`.
+#[derive(Clone, Serialize)]
+pub struct AdaptiveMechanism {
+    mechanism: ExplorationMechanism,
+    base: BaseAlgorithm,
+    boundary: Boundary,
+    initial_new_pop: u32,
+}
+
+impl AdaptiveMechanism {
+    pub fn from_params(
+        mechanism: ExplorationMechanism,
+        base: BaseAlgorithm,
+        boundary: Boundary,
+        initial_new_pop: u32,
+    ) -> Self {
+        Self { mechanism, base, boundary, initial_new_pop }
+    }
+
+    pub fn new<P>(
+        mechanism: ExplorationMechanism,
+        base: BaseAlgorithm,
+        boundary: Boundary,
+        initial_new_pop: u32,
+    ) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64> + KnownOptimumProblem,
+    {
+        Box::new(Self::from_params(mechanism, base, boundary, initial_new_pop))
+    }
+
+    /// The mechanism generating and replacing `new_pop` solutions.
+    fn with_new_pop<P>(&self, new_pop: u32) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64> + KnownOptimumProblem,
+    {
+        self.mechanism.mechanism(new_pop, &self.base, self.boundary)
+    }
+}
+
+impl<P> Component<P> for AdaptiveMechanism
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64> + KnownOptimumProblem,
+{
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        self.with_new_pop::<P>(self.initial_new_pop).init(problem, state)?;
+        state.insert(NewPop {
+            new_pop: self.initial_new_pop,
+            last_activation: None,
+        });
+        Ok(())
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        self.with_new_pop::<P>(self.initial_new_pop).require(problem, state_req)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let iteration = state.iterations();
+        let best = state.best_objective_value();
+
+        let mut new_pop = state.borrow_mut::<NewPop>();
+        new_pop.last_activation = Some((iteration, best));
+        let mechanism = self.with_new_pop::<P>(new_pop.new_pop);
+        drop(new_pop);
+
+        mechanism.execute(problem, state)
+    }
+}
+
+/// Adapts `new_pop` of the [`AdaptiveMechanism`] depending on the success of its last activation.
+///
+/// If a new global best is found within `window` iterations after the activation, `new_pop` is increased by `step`,
+/// otherwise it is decreased by `step`, staying within `min` and `max`.
+#[derive(Clone, Serialize)]
+pub struct NewPopAdaptation {
+    window: u32,
+    step: u32,
+    min: u32,
+    max: u32,
+}
+
+impl NewPopAdaptation {
+    pub fn from_params(window: u32, step: u32, min: u32, max: u32) -> Self {
+        Self { window, step, min, max }
+    }
+
+    pub fn new<P: SingleObjectiveProblem>(window: u32, step: u32, min: u32, max: u32) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(window, step, min, max))
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for NewPopAdaptation {
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, NewPop>()?;
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let iteration = state.iterations();
+        let best = state.best_objective_value();
+        let mut new_pop = state.borrow_mut::<NewPop>();
+
+        let Some((activation, best_before)) = new_pop.last_activation else {
+            return Ok(());
+        };
+
+        let improved = match (best, best_before) {
+            (Some(best), Some(best_before)) => best < best_before,
+            _ => false,
+        };
+
+        if improved {
+            new_pop.new_pop = (new_pop.new_pop + self.step).min(self.max);
+            new_pop.last_activation = None;
+        } else if iteration - activation >= self.window {
+            new_pop.new_pop = new_pop.new_pop.saturating_sub(self.step).max(self.min);
+            new_pop.last_activation = None;
+        }
+        Ok(())
+    }
+}
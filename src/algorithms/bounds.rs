@@ -0,0 +1,175 @@
+use std::ops::Range;
+
+use better_any::{Tid, TidAble};
+use derive_more::{Deref, DerefMut};
+use mahf::{prelude::*, CustomState,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem},
+           Random};
+use rand::Rng;
+use serde::Serialize;
+
+use super::parameters::Boundary;
+
+/// Number of solutions that violated the bounds so far, counted before they are corrected.
+///
+/// The number of violations in an iteration is the difference to the value of the previous iteration.
+#[derive(Default, Clone, Serialize, Deref, DerefMut, Tid)]
+pub struct BoundaryViolations(pub u32);
+
+impl CustomState<'_> for BoundaryViolations {}
+
+/// Moves the coordinate `x` back into the `range` with the `boundary` handling, if it violates the bounds; `best` is
+/// the coordinate of the best solution, if any.
+fn correct(boundary: Boundary, x: f64, range: &Range<f64>, best: Option<f64>, rng: &mut Random) -> f64 {
+    let (lower, upper) = (range.start, range.end);
+    if (lower..=upper).contains(&x) {
+        return x;
+    }
+    let bound = if x < lower { lower } else { upper };
+    let width = upper - lower;
+    match boundary {
+        Boundary::Clamp => bound,
+        Boundary::Reflect => {
+            // mirror at the bounds until the coordinate is within the domain
+            let offset = (x - lower).rem_euclid(2.0 * width);
+            if offset <= width { lower + offset } else { upper - (offset - width) }
+        }
+        Boundary::Periodic => lower + (x - lower).rem_euclid(width),
+        Boundary::Random => rng.gen_range(lower..=upper),
+        Boundary::Midpoint => match best {
+            Some(best) => (bound + best) / 2.0,
+            None => (bound + (lower + upper) / 2.0) / 2.0,
+        },
+        Boundary::Cosine => unreachable!("cosine correction is delegated to MAHF"),
+    }
+}
+
+/// Moves the coordinates of the current population that violate the bounds of the domain back into it.
+#[derive(Clone, Serialize)]
+#[serde(bound = "")]
+pub struct BoundaryHandler<P: Problem> {
+    boundary: Boundary,
+    /// Correction of MAHF used for [`Boundary::Cosine`].
+    cosine: Box<dyn Component<P>>,
+}
+
+impl<P> BoundaryHandler<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    pub fn from_params(boundary: Boundary) -> Self {
+        Self {
+            boundary,
+            cosine: boundary::CosineCorrection::new(),
+        }
+    }
+
+    pub fn new(boundary: Boundary) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(boundary))
+    }
+}
+
+impl<P> Component<P> for BoundaryHandler<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.has::<BoundaryViolations>() {
+            state.insert(BoundaryViolations::default());
+        }
+        self.cosine.init(problem, state)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let domain = problem.domain();
+        let best = state.best_individual().map(|best| best.solution().clone());
+        let mut populations = state.populations_mut();
+        let population = populations.current_mut();
+
+        let violations = population
+            .iter()
+            .filter(|individual| {
+                individual
+                    .solution()
+                    .iter()
+                    .zip(&domain)
+                    .any(|(x, range)| *x < range.start || *x > range.end)
+            })
+            .count();
+        state.borrow_mut::<BoundaryViolations>().0 += violations as u32;
+
+        if self.boundary == Boundary::Cosine {
+            drop(populations);
+            return self.cosine.execute(problem, state);
+        }
+        if violations == 0 {
+            return Ok(());
+        }
+
+        let mut rng = state.random_mut();
+        for individual in population.iter_mut() {
+            let solution: Vec<f64> = individual
+                .solution()
+                .iter()
+                .zip(&domain)
+                .enumerate()
+                .map(|(d, (&x, range))| correct(self.boundary, x, range, best.as_ref().map(|best| best[d]), &mut rng))
+                .collect();
+            if solution != *individual.solution() {
+                *individual = Individual::new_unevaluated(solution);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn correct_in(boundary: Boundary, x: f64, best: Option<f64>) -> f64 {
+        correct(boundary, x, &(-5.0..5.0), best, &mut Random::new(0))
+    }
+
+    #[test]
+    fn keeps_coordinates_within_the_domain() {
+        for boundary in [Boundary::Clamp, Boundary::Reflect, Boundary::Periodic, Boundary::Random, Boundary::Midpoint] {
+            assert_eq!(correct_in(boundary, 1.5, None), 1.5);
+            assert_eq!(correct_in(boundary, -5.0, None), -5.0);
+            assert_eq!(correct_in(boundary, 5.0, None), 5.0);
+        }
+    }
+
+    #[test]
+    fn clamps_to_the_violated_bound() {
+        assert_eq!(correct_in(Boundary::Clamp, 7.0, None), 5.0);
+        assert_eq!(correct_in(Boundary::Clamp, -9.0, None), -5.0);
+    }
+
+    #[test]
+    fn reflects_at_the_bounds() {
+        assert_eq!(correct_in(Boundary::Reflect, 6.0, None), 4.0);
+        assert_eq!(correct_in(Boundary::Reflect, -7.0, None), -3.0);
+        // reflected at both bounds
+        assert_eq!(correct_in(Boundary::Reflect, 17.0, None), -3.0);
+        assert_eq!(correct_in(Boundary::Reflect, -16.0, None), 4.0);
+    }
+
+    #[test]
+    fn wraps_around_periodically() {
+        assert_eq!(correct_in(Boundary::Periodic, 6.0, None), -4.0);
+        assert_eq!(correct_in(Boundary::Periodic, -7.0, None), 3.0);
+        assert_eq!(correct_in(Boundary::Periodic, 27.0, None), -3.0);
+    }
+
+    #[test]
+    fn moves_to_the_midpoint() {
+        assert_eq!(correct_in(Boundary::Midpoint, 8.0, Some(1.0)), 3.0);
+        assert_eq!(correct_in(Boundary::Midpoint, -8.0, None), -2.5);
+    }
+
+    #[test]
+    fn reinitialises_randomly_within_the_domain() {
+        for x in [-100.0, 6.0, 100.0] {
+            assert!((-5.0..=5.0).contains(&correct_in(Boundary::Random, x, None)));
+        }
+    }
+}
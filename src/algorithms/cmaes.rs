@@ -0,0 +1,395 @@
+use std::ops::Range;
+
+use better_any::{Tid, TidAble};
+use mahf::{prelude::*, state::StateReq, CustomState,
+           configuration::Configuration, logging::Logger,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+use nalgebra::{DMatrix, DVector, SymmetricEigen};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use serde::Serialize;
+
+use super::diversity::diversity_measures;
+use super::parameters::{Boundary, DiversityMeasure, RestartStrategy};
+use super::restart::{LastRestart, RestartCount};
+
+/// Distribution and evolution paths of one run of CMA-ES, following Hansen 2016, "The CMA Evolution Strategy:
+/// A Tutorial".
+struct CmaEsRun {
+    lambda: usize,
+    /// Recombination weights of the `mu = lambda / 2` best solutions.
+    weights: Vec<f64>,
+    mu_eff: f64,
+    c_c: f64,
+    c_s: f64,
+    c_1: f64,
+    c_mu: f64,
+    damps: f64,
+    /// Expected norm of a standard normally distributed vector.
+    chi_n: f64,
+    mean: DVector<f64>,
+    sigma: f64,
+    initial_sigma: f64,
+    c: DMatrix<f64>,
+    /// Eigenvectors of `c` and square roots of its eigenvalues.
+    b: DMatrix<f64>,
+    d: DVector<f64>,
+    p_c: DVector<f64>,
+    p_s: DVector<f64>,
+    generation: u32,
+    /// Best objective value of each generation.
+    best_values: Vec<f64>,
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_by(f64::total_cmp);
+    values[values.len() / 2]
+}
+
+impl CmaEsRun {
+    fn new(mean: Vec<f64>, sigma: f64, lambda: usize) -> Self {
+        let n = mean.len();
+        let nf = n as f64;
+
+        let weights: Vec<f64> = (1..=lambda / 2)
+            .map(|i| ((lambda as f64 + 1.0) / 2.0).ln() - (i as f64).ln())
+            .collect();
+        let sum: f64 = weights.iter().sum();
+        let weights: Vec<f64> = weights.iter().map(|w| w / sum).collect();
+        let mu_eff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+        let c_1 = 2.0 / ((nf + 1.3).powi(2) + mu_eff);
+        let c_s = (mu_eff + 2.0) / (nf + mu_eff + 5.0);
+        Self {
+            lambda,
+            weights,
+            mu_eff,
+            c_c: (4.0 + mu_eff / nf) / (nf + 4.0 + 2.0 * mu_eff / nf),
+            c_s,
+            c_1,
+            c_mu: (1.0 - c_1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((nf + 2.0).powi(2) + mu_eff)),
+            damps: 1.0 + 2.0 * (((mu_eff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + c_s,
+            chi_n: nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf)),
+            mean: DVector::from_vec(mean),
+            sigma,
+            initial_sigma: sigma,
+            c: DMatrix::identity(n, n),
+            b: DMatrix::identity(n, n),
+            d: DVector::from_element(n, 1.0),
+            p_c: DVector::zeros(n),
+            p_s: DVector::zeros(n),
+            generation: 0,
+            best_values: Vec::new(),
+        }
+    }
+
+    /// Samples `lambda` solutions from the current distribution.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Vec<f64>> {
+        (0..self.lambda)
+            .map(|_| {
+                let z = DVector::from_fn(self.mean.len(), |_, _| rng.sample::<f64, _>(StandardNormal));
+                let y = &self.b * self.d.component_mul(&z);
+                (&self.mean + y * self.sigma).as_slice().to_vec()
+            })
+            .collect()
+    }
+
+    /// Updates the distribution from the `solutions` sorted by their objective `values`.
+    ///
+    /// Returns whether the run has to be restarted, see [`CmaEsRun::terminated`].
+    fn update(&mut self, solutions: &[&Vec<f64>], values: &[f64]) -> bool {
+        let n = self.mean.len();
+        let nf = n as f64;
+
+        let ys: Vec<DVector<f64>> = solutions
+            .iter()
+            .take(self.weights.len())
+            .map(|x| (DVector::from_column_slice(x) - &self.mean) / self.sigma)
+            .collect();
+        let y_w = ys
+            .iter()
+            .zip(&self.weights)
+            .fold(DVector::zeros(n), |sum, (y, w)| sum + y * *w);
+        self.mean += &y_w * self.sigma;
+        self.generation += 1;
+
+        // evolution paths, with the cumulation of p_c stalled if p_s is large
+        let c_inv_sqrt = &self.b * DMatrix::from_diagonal(&self.d.map(|d| 1.0 / d)) * self.b.transpose();
+        self.p_s = &self.p_s * (1.0 - self.c_s)
+            + c_inv_sqrt * &y_w * (self.c_s * (2.0 - self.c_s) * self.mu_eff).sqrt();
+        let p_s_norm = self.p_s.norm() / (1.0 - (1.0 - self.c_s).powi(2 * self.generation as i32)).sqrt();
+        let h_s = if p_s_norm < (1.4 + 2.0 / (nf + 1.0)) * self.chi_n { 1.0 } else { 0.0 };
+        self.p_c = &self.p_c * (1.0 - self.c_c) + &y_w * (h_s * (self.c_c * (2.0 - self.c_c) * self.mu_eff).sqrt());
+
+        // rank-one and rank-mu update of the covariance matrix, and cumulative step-size adaptation
+        let rank_mu = ys
+            .iter()
+            .zip(&self.weights)
+            .fold(DMatrix::zeros(n, n), |sum, (y, w)| sum + y * y.transpose() * *w);
+        let decay = 1.0 - self.c_1 - self.c_mu + (1.0 - h_s) * self.c_1 * self.c_c * (2.0 - self.c_c);
+        self.c = &self.c * decay + &self.p_c * self.p_c.transpose() * self.c_1 + rank_mu * self.c_mu;
+        self.sigma *= ((self.c_s / self.damps) * (self.p_s.norm() / self.chi_n - 1.0)).exp();
+
+        // C = B D^2 B^T; a numerically broken distribution ends the run
+        self.c = (&self.c + self.c.transpose()) / 2.0;
+        let eigen = SymmetricEigen::new(self.c.clone());
+        if !eigen.eigenvalues.iter().all(|&e| e > 0.0) || !self.sigma.is_finite() {
+            return true;
+        }
+        self.b = eigen.eigenvectors;
+        self.d = eigen.eigenvalues.map(f64::sqrt);
+
+        self.best_values.push(values[0]);
+        self.terminated(values)
+    }
+
+    /// Default stopping criteria of IPOP- and BIPOP-CMA-ES (Hansen 2009): TolFun, TolX, NoEffectAxis,
+    /// NoEffectCoord, ConditionCov and Stagnation.
+    fn terminated(&self, values: &[f64]) -> bool {
+        let n = self.mean.len();
+        let nf = n as f64;
+        let generations = self.best_values.len();
+
+        let history = 10 + (30.0 * nf / self.lambda as f64).ceil() as usize;
+        let (min, max) = self.best_values[generations.saturating_sub(history)..]
+            .iter()
+            .chain(values)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| (min.min(v), max.max(v)));
+        let tol_fun = generations >= history && max - min < 1e-12;
+
+        let tol_x = (0..n).all(|i| {
+            self.sigma * self.p_c[i].abs().max(self.c[(i, i)].sqrt()) < 1e-12 * self.initial_sigma
+        });
+
+        let axis = self.generation as usize % n;
+        let no_effect_axis = (0..n).all(|i| {
+            self.mean[i] + 0.1 * self.sigma * self.d[axis] * self.b[(i, axis)] == self.mean[i]
+        });
+        let no_effect_coord = (0..n).any(|i| self.mean[i] + 0.2 * self.sigma * self.c[(i, i)].sqrt() == self.mean[i]);
+
+        let condition_cov = (self.d.max() / self.d.min()).powi(2) > 1e14;
+
+        // the median of the last 20 best values is not better than the median of the first 20 of a window
+        // spanning the last 20% of the generations
+        let window = ((0.2 * generations as f64) as usize).max(120 + (30.0 * nf / self.lambda as f64) as usize);
+        let stagnation = generations >= window && {
+            let recent = &self.best_values[generations - window..];
+            median(&recent[recent.len() - 20..]) >= median(&recent[..20])
+        };
+
+        tol_fun || tol_x || no_effect_axis || no_effect_coord || condition_cov || stagnation
+    }
+}
+
+/// Regime of a BIPOP-CMA-ES run.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Regime {
+    /// Population size doubled with each run of this regime.
+    Large,
+    /// Small population size and step size, both randomised.
+    Small,
+}
+
+/// Current run and restart schedule of CMA-ES.
+#[derive(Default, Tid)]
+pub struct CmaEsRuns {
+    /// `None` if a new run has to be started.
+    run: Option<CmaEsRun>,
+    /// Number of runs started so far.
+    runs: u32,
+    /// Regime of the current run; `None` for the first run, whose evaluations count towards neither budget.
+    regime: Option<Regime>,
+    large_runs: u32,
+    /// Evaluations spent in each regime.
+    large_budget: u32,
+    small_budget: u32,
+    /// Evaluations at the start of the current run.
+    run_start: u32,
+}
+
+impl CustomState<'_> for CmaEsRuns {}
+
+impl CmaEsRuns {
+    /// Starts the next run with the population size and step size of the restart `strategy`; the population size
+    /// is limited by the `remaining` evaluations.
+    fn start<R: Rng + ?Sized>(
+        &mut self,
+        strategy: RestartStrategy,
+        domain: &[Range<f64>],
+        evaluations: u32,
+        remaining: u32,
+        rng: &mut R,
+    ) {
+        let n = domain.len() as f64;
+        let default_lambda = 4 + (3.0 * n.ln()).floor() as usize;
+        let default_sigma = 0.2 * domain.iter().map(|range| range.end - range.start).sum::<f64>() / n;
+        let doubled = |times: u32| default_lambda.saturating_mul(2usize.saturating_pow(times));
+
+        let used = evaluations - self.run_start;
+        match self.regime {
+            Some(Regime::Large) => self.large_budget += used,
+            Some(Regime::Small) => self.small_budget += used,
+            None => {}
+        }
+
+        let (lambda, sigma, regime) = match strategy {
+            _ if self.runs == 0 => (default_lambda, default_sigma, None),
+            RestartStrategy::Ipop => (doubled(self.runs), default_sigma, Some(Regime::Large)),
+            RestartStrategy::Bipop if self.small_budget < self.large_budget => {
+                let u: f64 = rng.gen();
+                let large_lambda = doubled(self.large_runs) as f64;
+                let lambda = default_lambda as f64 * (0.5 * large_lambda / default_lambda as f64).powf(u * u);
+                (lambda.floor() as usize, default_sigma * 10f64.powf(-2.0 * u), Some(Regime::Small))
+            }
+            RestartStrategy::Bipop => {
+                self.large_runs += 1;
+                (doubled(self.large_runs), default_sigma, Some(Regime::Large))
+            }
+        };
+        let lambda = lambda.min(remaining as usize).max(default_lambda);
+        let mean = domain.iter().map(|range| rng.gen_range(range.clone())).collect();
+
+        self.run = Some(CmaEsRun::new(mean, sigma, lambda));
+        self.runs += 1;
+        self.regime = regime;
+        self.run_start = evaluations;
+    }
+}
+
+/// Samples a new population from the distribution of the current run of CMA-ES, starting a new run with the
+/// restart `strategy` if the previous one terminated.
+#[derive(Clone, Serialize)]
+pub struct CmaEsSample {
+    strategy: RestartStrategy,
+    /// Evaluation budget of the whole optimization.
+    evaluations: u32,
+}
+
+impl CmaEsSample {
+    pub fn from_params(strategy: RestartStrategy, evaluations: u32) -> Self {
+        Self { strategy, evaluations }
+    }
+
+    pub fn new<P>(strategy: RestartStrategy, evaluations: u32) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(strategy, evaluations))
+    }
+}
+
+impl<P> Component<P> for CmaEsSample
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(CmaEsRuns::default());
+        state.insert(RestartCount::default());
+        state.insert(LastRestart::default());
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let evaluations = state.evaluations();
+        let mut runs = state.borrow_mut::<CmaEsRuns>();
+        let mut rng = state.random_mut();
+
+        if runs.run.is_none() {
+            if runs.runs > 0 {
+                state.borrow_mut::<RestartCount>().0 += 1;
+                state.borrow_mut::<LastRestart>().0 = Some(evaluations);
+            }
+            let remaining = self.evaluations.saturating_sub(evaluations);
+            runs.start(self.strategy, &problem.domain(), evaluations, remaining, &mut *rng);
+        }
+        let population = runs
+            .run
+            .as_ref()
+            .expect("a run was started")
+            .sample(&mut *rng)
+            .into_iter()
+            .map(Individual::new_unevaluated)
+            .collect();
+
+        drop(runs);
+        drop(rng);
+        state.populations_mut().push(population);
+        Ok(())
+    }
+}
+
+/// Removes the evaluated population and updates the distribution of the current run of CMA-ES with it,
+/// ending the run if one of its stopping criteria is met.
+#[derive(Clone, Serialize)]
+pub struct CmaEsUpdate;
+
+impl CmaEsUpdate {
+    pub fn from_params() -> Self {
+        Self
+    }
+
+    pub fn new<P>() -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params())
+    }
+}
+
+impl<P> Component<P> for CmaEsUpdate
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, CmaEsRuns>()?;
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut population = state.populations_mut().pop();
+        population.sort_by(|a, b| a.objective().value().total_cmp(&b.objective().value()));
+        let solutions: Vec<_> = population.iter().map(|individual| individual.solution()).collect();
+        let values: Vec<_> = population.iter().map(|individual| individual.objective().value()).collect();
+
+        let mut runs = state.borrow_mut::<CmaEsRuns>();
+        let run = runs.run.as_mut().expect("CmaEsSample starts a run before each update");
+        if run.update(&solutions, &values) {
+            runs.run = None;
+        }
+        Ok(())
+    }
+}
+
+/// IPOP- or BIPOP-CMA-ES, depending on the restart `strategy`.
+///
+/// Each run starts from a uniformly random mean within the domain with a step size of 0.2 times the mean domain
+/// width. Restarts are counted in [`RestartCount`] and [`LastRestart`]. The first sample is logged as the initial
+/// population, like in the other algorithms.
+pub fn cma_es<P>(
+    evaluations: u32,
+    strategy: RestartStrategy,
+    boundary: Boundary,
+    diversity: &[DiversityMeasure],
+) -> Configuration<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    Configuration::builder()
+        .do_(CmaEsSample::new(strategy, evaluations))
+        .do_(boundary.operator())
+        .evaluate()
+        .update_best_individual()
+        .do_(diversity_measures(diversity))
+        .do_(Logger::new())
+        .do_(CmaEsUpdate::new())
+        .while_(
+            conditions::LessThanN::evaluations(evaluations),
+            |builder| {
+                builder
+                    .do_(CmaEsSample::new(strategy, evaluations))
+                    .do_(boundary.operator())
+                    .evaluate()
+                    .update_best_individual()
+                    .do_(diversity_measures(diversity))
+                    .do_(Logger::new())
+                    .do_(CmaEsUpdate::new())
+            }
+        )
+        .build()
+}
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use better_any::{Tid, TidAble};
+use derive_more::{Deref, DerefMut};
+use mahf::{prelude::*, CustomState,
+           components::measures::diversity::MinimumIndividualDistance,
+           configuration::Configuration,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+use serde::Serialize;
+
+use super::parameters::DiversityMeasure;
+
+/// Normalized diversity of the current population for each measure computed in this run, by name of the measure.
+#[derive(Default, Clone, Serialize, Deref, DerefMut, Tid)]
+pub struct Diversities(pub BTreeMap<String, f64>);
+
+impl CustomState<'_> for Diversities {}
+
+/// Largest value of each measure so far, which the diversity is normalized by.
+#[derive(Default, Tid)]
+struct DiversityMaxima(BTreeMap<String, f64>);
+
+impl CustomState<'_> for DiversityMaxima {}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+}
+
+fn centroid(solutions: &[&Vec<f64>]) -> Vec<f64> {
+    let n = solutions.len() as f64;
+    (0..solutions[0].len())
+        .map(|d| solutions.iter().map(|solution| solution[d]).sum::<f64>() / n)
+        .collect()
+}
+
+impl DiversityMeasure {
+    /// Raw diversity of the `solutions`; 0 for less than two solutions.
+    pub fn measure(self, solutions: &[&Vec<f64>]) -> f64 {
+        let n = solutions.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let pairs = || (0..n).flat_map(move |i| (i + 1..n).map(move |j| (i, j)));
+
+        match self {
+            DiversityMeasure::MinimumDistance => pairs()
+                .map(|(i, j)| distance(solutions[i], solutions[j]))
+                .fold(f64::INFINITY, f64::min),
+            DiversityMeasure::DimensionWise => {
+                // mean absolute deviation from the median of each dimension, averaged over the dimensions
+                let dimensions = solutions[0].len();
+                (0..dimensions)
+                    .map(|d| {
+                        let mut values: Vec<f64> = solutions.iter().map(|solution| solution[d]).collect();
+                        values.sort_by(f64::total_cmp);
+                        let median = values[n / 2];
+                        values.iter().map(|x| (x - median).abs()).sum::<f64>() / n as f64
+                    })
+                    .sum::<f64>()
+                    / dimensions as f64
+            }
+            DiversityMeasure::Pairwise => {
+                let sum: f64 = pairs().map(|(i, j)| distance(solutions[i], solutions[j])).sum();
+                sum / (n * (n - 1) / 2) as f64
+            }
+            DiversityMeasure::DistanceToCentroid => {
+                let centroid = centroid(solutions);
+                solutions.iter().map(|solution| distance(solution, &centroid)).sum::<f64>() / n as f64
+            }
+            DiversityMeasure::Radius => {
+                let centroid = centroid(solutions);
+                solutions
+                    .iter()
+                    .map(|solution| distance(solution, &centroid))
+                    .fold(0.0, f64::max)
+            }
+            DiversityMeasure::TrueDiversity => {
+                // square root of the sum of the variances of all dimensions (Wineberg and Oppacher 2003)
+                let centroid = centroid(solutions);
+                let variance: f64 = solutions
+                    .iter()
+                    .map(|solution| distance(solution, &centroid).powi(2))
+                    .sum::<f64>()
+                    / n as f64;
+                variance.sqrt()
+            }
+        }
+    }
+}
+
+/// Diversity of the current population with the `measure`, normalized by the largest value so far.
+fn normalized<P>(measure: DiversityMeasure, state: &mut State<P>) -> f64
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    let populations = state.populations();
+    let solutions: Vec<_> = populations.current().iter().map(|individual| individual.solution()).collect();
+    let value = measure.measure(&solutions);
+
+    let mut maxima = state.borrow_mut::<DiversityMaxima>();
+    let max = maxima.0.entry(measure.to_string()).or_insert(0.0);
+    *max = max.max(value);
+    if *max > 0.0 { value / *max } else { 0.0 }
+}
+
+/// Computes the normalized diversity of the current population with each of the `measures`, see [`Diversities`].
+#[derive(Clone, Serialize)]
+pub struct MeasureDiversity {
+    measures: Vec<DiversityMeasure>,
+}
+
+impl MeasureDiversity {
+    pub fn from_params(measures: Vec<DiversityMeasure>) -> Self {
+        Self { measures }
+    }
+
+    pub fn new<P>(measures: Vec<DiversityMeasure>) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(measures))
+    }
+}
+
+impl<P> Component<P> for MeasureDiversity
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(Diversities::default());
+        if !state.has::<DiversityMaxima>() {
+            state.insert(DiversityMaxima::default());
+        }
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        for &measure in &self.measures {
+            let value = normalized(measure, state);
+            state.borrow_mut::<Diversities>().0.insert(measure.to_string(), value);
+        }
+        Ok(())
+    }
+}
+
+/// Measures the diversity of the population: the minimum individual distance of MAHF, which is always logged,
+/// and the additional `measures`.
+pub fn diversity_measures<P>(measures: &[DiversityMeasure]) -> Box<dyn Component<P>>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    if measures.is_empty() {
+        return MinimumIndividualDistance::new();
+    }
+    Configuration::builder()
+        .do_(MinimumIndividualDistance::new())
+        .do_(MeasureDiversity::new(measures.to_vec()))
+        .build_component()
+}
+
+/// True if the normalized diversity of the population with the `measure` is below the `threshold`.
+#[derive(Clone, Serialize)]
+pub struct DiversityBelow {
+    measure: DiversityMeasure,
+    threshold: f64,
+}
+
+impl DiversityBelow {
+    pub fn from_params(measure: DiversityMeasure, threshold: f64) -> Self {
+        Self { measure, threshold }
+    }
+
+    pub fn new<P>(measure: DiversityMeasure, threshold: f64) -> Box<dyn Condition<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(measure, threshold))
+    }
+}
+
+impl<P> Condition<P> for DiversityBelow
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.has::<DiversityMaxima>() {
+            state.insert(DiversityMaxima::default());
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        Ok(normalized(self.measure, state) < self.threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-12, "expected {}, found {}", expected, actual);
+    }
+
+    #[test]
+    fn measures_a_triangle() {
+        let (a, b, c) = (vec![0.0, 0.0], vec![4.0, 0.0], vec![0.0, 3.0]);
+        let solutions = [&a, &b, &c];
+
+        assert_close(DiversityMeasure::MinimumDistance.measure(&solutions), 3.0);
+        assert_close(DiversityMeasure::DimensionWise.measure(&solutions), 7.0 / 6.0);
+        assert_close(DiversityMeasure::Pairwise.measure(&solutions), 4.0);
+        assert_close(
+            DiversityMeasure::DistanceToCentroid.measure(&solutions),
+            (5.0 + 73f64.sqrt() + 52f64.sqrt()) / 9.0,
+        );
+        assert_close(DiversityMeasure::Radius.measure(&solutions), 73f64.sqrt() / 3.0);
+        assert_close(DiversityMeasure::TrueDiversity.measure(&solutions), 50f64.sqrt() / 3.0);
+    }
+
+    #[test]
+    fn is_zero_without_spread() {
+        let (a, b) = (vec![1.0, 2.0], vec![1.0, 2.0]);
+        for measure in [
+            DiversityMeasure::MinimumDistance,
+            DiversityMeasure::DimensionWise,
+            DiversityMeasure::Pairwise,
+            DiversityMeasure::DistanceToCentroid,
+            DiversityMeasure::Radius,
+            DiversityMeasure::TrueDiversity,
+        ] {
+            assert_eq!(measure.measure(&[&a]), 0.0);
+            assert_eq!(measure.measure(&[&a, &b]), 0.0);
+        }
+    }
+}
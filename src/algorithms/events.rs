@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+
+use better_any::{Tid, TidAble};
+use mahf::{prelude::*, CustomState,
+           components::measures::diversity::{Diversity, MinimumIndividualDistance},
+           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+use serde::Serialize;
+
+use super::bounds::BoundaryViolations;
+
+/// Activation of an exploration mechanism.
+#[derive(Clone, Debug, Serialize)]
+pub struct MechanismEvent {
+    /// Name of the mechanism, e.g. `NPGM`.
+    pub mechanism: &'static str,
+    pub iteration: u32,
+    /// Evaluations before the activation.
+    pub evaluations: u32,
+    /// Minimum individual distance before and after the activation.
+    pub diversity_before: f64,
+    pub diversity_after: f64,
+    /// Best objective value before and after the activation.
+    pub best_before: Option<f64>,
+    pub best_after: Option<f64>,
+    /// Indices of the replaced solutions in the population after the activation.
+    pub replaced: Vec<usize>,
+    /// Number of generated solutions that violated the bounds and were corrected.
+    pub clipped: u32,
+    /// Whether a replaced solution, or a solution derived from it at the same index, became the global best
+    /// within the attribution window, see [`AttributeSuccess`].
+    pub success: bool,
+    /// Improvement of the best objective value by these new global bests.
+    pub improvement: f64,
+    /// Index of the arm and its reward, if the mechanism was selected as arm of a portfolio, see
+    /// `MechanismPortfolio`.
+    pub arm: Option<usize>,
+    pub reward: Option<f64>,
+}
+
+/// Activations of the exploration mechanisms so far.
+#[derive(Default, Tid)]
+pub struct MechanismEvents(pub Vec<MechanismEvent>);
+
+impl CustomState<'_> for MechanismEvents {}
+
+/// Success of the activations of one exploration mechanism.
+#[derive(Default, Clone, Debug, Serialize)]
+pub struct MechanismSuccess {
+    pub activations: u32,
+    pub successes: u32,
+    pub success_rate: f64,
+    /// Total improvement of the best objective value by successful activations.
+    pub improvement: f64,
+}
+
+impl MechanismEvents {
+    /// Success of the activations of each mechanism, by name of the mechanism.
+    pub fn success(&self) -> BTreeMap<&'static str, MechanismSuccess> {
+        let mut success: BTreeMap<_, MechanismSuccess> = BTreeMap::new();
+        for event in &self.0 {
+            let mechanism = success.entry(event.mechanism).or_default();
+            mechanism.activations += 1;
+            mechanism.successes += event.success as u32;
+            mechanism.improvement += event.improvement;
+        }
+        for mechanism in success.values_mut() {
+            mechanism.success_rate = mechanism.successes as f64 / mechanism.activations as f64;
+        }
+        success
+    }
+}
+
+/// Activation that generated the solution at an index of the population.
+#[derive(Clone, Copy)]
+struct Origin {
+    /// Index of the activation in [`MechanismEvents`].
+    event: usize,
+    iteration: u32,
+}
+
+/// Origin of the solution at each index of the population; `None` for solutions of the base algorithm.
+#[derive(Default, Tid)]
+pub struct Origins(Vec<Option<Origin>>);
+
+impl CustomState<'_> for Origins {}
+
+/// Best objective value after the previous iteration.
+#[derive(Default, Tid)]
+struct PreviousBest(Option<f64>);
+
+impl CustomState<'_> for PreviousBest {}
+
+/// Indices of the solutions replaced by the current activation of an exploration mechanism.
+#[derive(Default, Tid)]
+pub struct ReplacedIndices(pub(crate) Vec<usize>);
+
+impl CustomState<'_> for ReplacedIndices {}
+
+/// Executes the exploration `mechanism` and records a [`MechanismEvent`] for it.
+#[derive(Clone, Serialize)]
+#[serde(bound = "")]
+pub struct RecordedMechanism<P: Problem> {
+    name: &'static str,
+    /// Whether the mechanism reorders the population, so that the origins of all solutions are lost, e.g. restarts.
+    clears_origins: bool,
+    mechanism: Box<dyn Component<P>>,
+    diversity: Box<dyn Component<P>>,
+}
+
+impl<P> RecordedMechanism<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    pub fn from_params(name: &'static str, clears_origins: bool, mechanism: Box<dyn Component<P>>) -> Self {
+        Self {
+            name,
+            clears_origins,
+            mechanism,
+            diversity: MinimumIndividualDistance::new(),
+        }
+    }
+
+    pub fn new(name: &'static str, clears_origins: bool, mechanism: Box<dyn Component<P>>) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(name, clears_origins, mechanism))
+    }
+
+    fn diversity(&self, problem: &P, state: &mut State<P>) -> ExecResult<f64> {
+        self.diversity.execute(problem, state)?;
+        Ok(state.get_value::<Diversity<MinimumIndividualDistance>>())
+    }
+}
+
+impl<P> Component<P> for RecordedMechanism<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.has::<MechanismEvents>() {
+            state.insert(MechanismEvents::default());
+        }
+        if !state.has::<Origins>() {
+            state.insert(Origins::default());
+        }
+        if !state.has::<ReplacedIndices>() {
+            state.insert(ReplacedIndices::default());
+        }
+        if !state.has::<BoundaryViolations>() {
+            state.insert(BoundaryViolations::default());
+        }
+        self.diversity.init(problem, state)?;
+        self.mechanism.init(problem, state)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let iteration = state.iterations();
+        let evaluations = state.evaluations();
+        let diversity_before = self.diversity(problem, state)?;
+        let best_before = state.best_objective_value().map(|best| best.value());
+        let violations_before = state.borrow::<BoundaryViolations>().0;
+        state.borrow_mut::<ReplacedIndices>().0.clear();
+
+        self.mechanism.execute(problem, state)?;
+
+        let event = MechanismEvent {
+            mechanism: self.name,
+            iteration,
+            evaluations,
+            diversity_before,
+            diversity_after: self.diversity(problem, state)?,
+            best_before,
+            best_after: state.best_objective_value().map(|best| best.value()),
+            replaced: std::mem::take(&mut state.borrow_mut::<ReplacedIndices>().0),
+            clipped: state.borrow::<BoundaryViolations>().0 - violations_before,
+            success: false,
+            improvement: 0.0,
+            arm: None,
+            reward: None,
+        };
+
+        // tag the replaced solutions
+        let size = state.populations().current().len();
+        let mut events = state.borrow_mut::<MechanismEvents>();
+        let mut origins = state.borrow_mut::<Origins>();
+        if self.clears_origins {
+            origins.0.clear();
+        }
+        origins.0.resize(size, None);
+        for &i in &event.replaced {
+            origins.0[i] = Some(Origin { event: events.0.len(), iteration });
+        }
+        events.0.push(event);
+        Ok(())
+    }
+}
+
+/// Attributes new global bests to the activation of the exploration mechanism that generated the solution at the
+/// index of the new global best, if the activation was at most `window` iterations ago.
+///
+/// Has to be executed once per iteration, after the best individual is updated.
+#[derive(Clone, Serialize)]
+pub struct AttributeSuccess {
+    window: u32,
+}
+
+impl AttributeSuccess {
+    pub fn from_params(window: u32) -> Self {
+        Self { window }
+    }
+
+    pub fn new<P: SingleObjectiveProblem>(window: u32) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(window))
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for AttributeSuccess {
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(PreviousBest::default());
+        if !state.has::<MechanismEvents>() {
+            state.insert(MechanismEvents::default());
+        }
+        if !state.has::<Origins>() {
+            state.insert(Origins::default());
+        }
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let best = state.best_objective_value().map(|best| best.value());
+        let previous = std::mem::replace(&mut state.borrow_mut::<PreviousBest>().0, best);
+        let (Some(best), Some(previous)) = (best, previous) else {
+            return Ok(());
+        };
+        if best >= previous {
+            return Ok(());
+        }
+
+        let populations = state.populations();
+        let Some(index) = populations
+            .current()
+            .iter()
+            .position(|individual| individual.objective().value() == best)
+        else {
+            return Ok(());
+        };
+        let iteration = state.iterations();
+        let origins = state.borrow::<Origins>();
+        if let Some(Some(origin)) = origins.0.get(index) {
+            if iteration - origin.iteration <= self.window {
+                let mut events = state.borrow_mut::<MechanismEvents>();
+                let event = &mut events.0[origin.event];
+                event.success = true;
+                event.improvement += previous - best;
+            }
+        }
+        Ok(())
+    }
+}
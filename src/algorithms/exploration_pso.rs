@@ -0,0 +1,263 @@
+use mahf::{prelude::*,
+           configuration::Configuration, logging::Logger,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem, KnownOptimumProblem}};
+use mahf::identifier::Global;
+use serde::Serialize;
+
+use super::adaptation::{AdaptiveMechanism, NewPopAdaptation};
+use super::events::{AttributeSuccess, RecordedMechanism};
+use super::diversity::diversity_measures;
+use super::parameters::{Boundary, DiversityMeasure, Initialization, PersonalBestPolicy, ReferenceSolution, Replacement, TerminationType, Topology, VelocityPolicy};
+use super::portfolio::{CreditAssignment, MechanismPortfolio};
+use super::replacement::ReplacedPersonalBests;
+use super::restart::{RandomRestart, RestartParams, RestartedParticles};
+use super::sampling::OppositionSelection;
+use super::velocity::{UpdateRule, VelocityLimit};
+
+/// Exploration mechanism that is executed instead of the regular update of the base algorithm, e.g. the PSO
+/// velocity update, whenever the exploration condition is met.
+#[derive(Clone, Serialize)]
+pub enum ExplorationMechanism {
+    /// Random restart; the swarm is (partially) re-initialised, see [`RestartParams`].
+    RandomRestart(RestartParams),
+    /// New Population Generation Mechanism (Cyclic Universe Mechanism of BBBC).
+    Npgm {
+        new_pop: u32,
+        replacement: Replacement,
+    },
+    /// Gbest-guided Population Generation Mechanism (Nuclear Reaction Mechanism of NFNF).
+    Gpgm {
+        new_pop: u32,
+        mu: f64,
+        termination_type: TerminationType,
+        termination_value: usize,
+        replacement: Replacement,
+    },
+    /// Solution Replacement Mechanism (Mine Explosion Dynamics of MBA).
+    Srm {
+        new_pop: u32,
+        center: ReferenceSolution,
+        replacement: Replacement,
+    },
+    /// Population Dispersion Mechanism (Negatively Charged Stepped Leader of LSA).
+    Pdm {
+        new_pop: u32,
+        leader: ReferenceSolution,
+        replacement: Replacement,
+    },
+    /// Portfolio of mechanisms; each time the exploration is triggered, one of the `arms` is selected by the
+    /// `credit` assignment rule, see [`MechanismPortfolio`].
+    Portfolio {
+        arms: Vec<ExplorationMechanism>,
+        credit: CreditAssignment,
+    },
+}
+
+/// Handling of the particles replaced by an exploration mechanism.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct ParticlePolicy {
+    pub personal_best: PersonalBestPolicy,
+    pub velocity: VelocityPolicy,
+}
+
+impl Default for ParticlePolicy {
+    /// Keeps the better personal best and draws a random velocity.
+    fn default() -> Self {
+        Self {
+            personal_best: PersonalBestPolicy::KeepBetter,
+            velocity: VelocityPolicy::Random,
+        }
+    }
+}
+
+/// Algorithm the exploration mechanism is embedded in, determining how the state of replaced solutions is handled.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum BaseAlgorithm {
+    /// PSO; replaced particles are handled according to the `policy`, with velocities limited by `v_max`,
+    /// and random restarts re-initialise the swarm.
+    Pso { v_max: VelocityLimit, policy: ParticlePolicy },
+    /// DE, e.g. SHADE; only the individuals are replaced.
+    De,
+}
+
+/// Adaptation of the number of solutions generated and replaced by the exploration mechanism,
+/// see [`NewPopAdaptation`].
+#[derive(Clone, Copy)]
+pub struct NewPopAdaptationParams {
+    /// Number of iterations after an activation in which a new global best counts as success.
+    pub window: u32,
+    /// Change of `new_pop` after each rated activation.
+    pub step: u32,
+}
+
+impl ExplorationMechanism {
+    /// Generates `new_pop` new solutions, moves them into the domain according to the `boundary` handling and
+    /// replaces them in the swarm.
+    pub(crate) fn mechanism<P>(&self, new_pop: u32, base: &BaseAlgorithm, boundary: Boundary) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64> + KnownOptimumProblem,
+    {
+        let (generator, replacement): (Box<dyn Component<P>>, _) = match self {
+            ExplorationMechanism::RandomRestart(_) | ExplorationMechanism::Portfolio { .. } => {
+                unreachable!("only the single mechanisms generate new solutions")
+            }
+            ExplorationMechanism::Npgm { replacement, .. } => (
+                swarm::bbbc::CyclicUniverseMechanism::new(new_pop),
+                replacement,
+            ),
+            ExplorationMechanism::Gpgm { mu, termination_type, termination_value, replacement, .. } => (
+                swarm::nfnf::NuclearReactionMechanism::new(new_pop, *mu, termination_type.to_string(), *termination_value),
+                replacement,
+            ),
+            ExplorationMechanism::Srm { center, replacement, .. } => (
+                swarm::mba::MineExplosionDynamics::new(new_pop, center.to_string()),
+                replacement,
+            ),
+            ExplorationMechanism::Pdm { leader, replacement, .. } => (
+                swarm::lsa::NegativelyChargedSteppedLeader::new(new_pop, leader.to_string()),
+                replacement,
+            ),
+        };
+
+        Configuration::builder()
+            .do_(selection::All::new())
+            .do_(generator)
+            .do_(boundary.operator())
+            .do_(replacement.operator(new_pop, base))
+            .build_component()
+    }
+
+    /// Name of the mechanism in the event log.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExplorationMechanism::RandomRestart(_) => "RR",
+            ExplorationMechanism::Npgm { .. } => "NPGM",
+            ExplorationMechanism::Gpgm { .. } => "GPGM",
+            ExplorationMechanism::Srm { .. } => "SRM",
+            ExplorationMechanism::Pdm { .. } => "PDM",
+            ExplorationMechanism::Portfolio { .. } => "PORTFOLIO",
+        }
+    }
+
+    /// Number of solutions generated and replaced by the mechanism, for the mechanisms replacing a fixed number.
+    pub(crate) fn new_pop(&self) -> Option<u32> {
+        match self {
+            ExplorationMechanism::RandomRestart(_) | ExplorationMechanism::Portfolio { .. } => None,
+            ExplorationMechanism::Npgm { new_pop, .. }
+            | ExplorationMechanism::Gpgm { new_pop, .. }
+            | ExplorationMechanism::Srm { new_pop, .. }
+            | ExplorationMechanism::Pdm { new_pop, .. } => Some(*new_pop),
+        }
+    }
+
+    /// Whether the mechanism replaces a fixed number of solutions `new_pop`, which can be adapted.
+    pub(crate) fn replaces_new_pop(&self) -> bool {
+        // random restarts and portfolios do not replace a fixed number of solutions
+        !matches!(self, ExplorationMechanism::RandomRestart(_) | ExplorationMechanism::Portfolio { .. })
+    }
+
+    /// Builds the mechanism, including the evaluation of the new solutions and the update of the best individual.
+    ///
+    /// Each activation of a single mechanism, also as arm of a portfolio, is recorded as `MechanismEvent`.
+    pub(crate) fn component<P>(
+        &self,
+        base: &BaseAlgorithm,
+        adaptation: Option<NewPopAdaptationParams>,
+        boundary: Boundary,
+        initialization: Initialization,
+    ) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64> + KnownOptimumProblem,
+    {
+        let new_pop = match self {
+            ExplorationMechanism::RandomRestart(restart) => {
+                assert!(
+                    restart.growth == 1.0 || matches!(base, BaseAlgorithm::Pso { .. }),
+                    "population growth on restart is only supported for PSO"
+                );
+                let builder = Configuration::builder()
+                    .do_(RandomRestart::new(*restart, initialization))
+                    .evaluate_with::<Global>()
+                    .do_if_some_((initialization == Initialization::Opposition).then(OppositionSelection::new))
+                    .update_best_individual();
+                let restart = match base {
+                    BaseAlgorithm::Pso { v_max, .. } => builder.do_(RestartedParticles::new(v_max.clone())),
+                    BaseAlgorithm::De => builder,
+                }
+                .build_component();
+                return RecordedMechanism::new(self.name(), true, restart); // restarts reorder the population
+            }
+            ExplorationMechanism::Portfolio { arms, credit } => {
+                return MechanismPortfolio::new(
+                    arms.iter().map(|arm| arm.component(base, None, boundary, initialization)).collect(),
+                    *credit,
+                );
+            }
+            ExplorationMechanism::Npgm { new_pop, .. }
+            | ExplorationMechanism::Gpgm { new_pop, .. }
+            | ExplorationMechanism::Srm { new_pop, .. }
+            | ExplorationMechanism::Pdm { new_pop, .. } => *new_pop,
+        };
+
+        let mechanism = match adaptation {
+            None => self.mechanism(new_pop, base, boundary),
+            Some(_) => AdaptiveMechanism::new(self.clone(), base.clone(), boundary, new_pop),
+        };
+
+        let mechanism = Configuration::builder()
+            .do_(mechanism)
+            .evaluate_with::<Global>()
+            .update_best_individual()
+            .build_component();
+        RecordedMechanism::new(self.name(), false, mechanism)
+    }
+}
+
+pub fn exploration_pso<P>(
+    evaluations: u32,
+    population_size: u32,
+    rule: UpdateRule,
+    v_max: VelocityLimit,
+    topology: Topology,
+    condition: Box<dyn Condition<P>>,
+    mechanism: ExplorationMechanism,
+    adaptation: Option<NewPopAdaptationParams>,
+    attribution_window: u32,
+    policy: ParticlePolicy,
+    boundary: Boundary,
+    initialization: Initialization,
+    diversity: &[DiversityMeasure],
+) -> Configuration<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64> + KnownOptimumProblem,
+{
+    let adaptation = adaptation.filter(|_| mechanism.replaces_new_pop());
+    let base = BaseAlgorithm::Pso { v_max: v_max.clone(), policy };
+    let exploration = mechanism.component(&base, adaptation, boundary, initialization);
+
+    Configuration::builder()
+        .do_(initialization.operator(population_size))
+        .update_best_individual()
+        .do_(v_max.swarm_init())
+        .do_(diversity_measures(diversity))
+        .do_(Logger::new())
+        .while_(
+            conditions::LessThanN::evaluations(evaluations),
+            |builder| {
+                builder
+                    .if_else_(condition, |builder| builder.do_(exploration), |builder| {
+                        builder
+                            .do_(topology.velocity_update(rule, &v_max, evaluations))
+                            .do_(boundary.operator())
+                            .evaluate_with::<Global>()
+                            .update_best_individual()
+                    })
+                    .do_(AttributeSuccess::new(attribution_window))
+                    .do_if_some_(adaptation.map(|adaptation| {
+                        NewPopAdaptation::new(adaptation.window, adaptation.step, 1, population_size)
+                    }))
+                    .do_(diversity_measures(diversity))
+                    .do_(swarm::pso::ParticleSwarmUpdate::new())
+                    .do_(ReplacedPersonalBests::new(policy.personal_best))
+                    .do_(Logger::new())
+            },
+        )
+        .build()
+}
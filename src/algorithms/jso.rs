@@ -0,0 +1,323 @@
+use better_any::{Tid, TidAble};
+use mahf::{prelude::*, CustomState,
+           configuration::Configuration, logging::Logger,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+use rand::Rng;
+use rand_distr::{Cauchy, Distribution, Normal};
+use serde::Serialize;
+
+use super::diversity::diversity_measures;
+use super::lshade::LinearPopulationReduction;
+use super::parameters::{Boundary, DiversityMeasure, Initialization};
+
+/// Parameters of jSO.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct JsoParams {
+    /// Population size at the start, reduced linearly to `min_size` over the evaluation budget.
+    pub initial_size: u32,
+    pub min_size: u32,
+    /// Number of entries of the memory of F and CR, including the last entry fixed to 0.9.
+    pub history: usize,
+    /// Size of the archive relative to the current population size.
+    pub archive_rate: f64,
+    /// Initial fraction of the best solutions the pbest is chosen from, decreased linearly to half this value.
+    pub p_max: f64,
+}
+
+impl JsoParams {
+    /// jSO (Brest, Maučec and Bošković 2017) with an initial population of `25 ln(D) sqrt(D)` for `D` dimensions.
+    pub fn new(dimensions: usize, history: usize, archive_rate: f64, p_max: f64) -> Self {
+        let d = dimensions as f64;
+        Self {
+            initial_size: ((25.0 * d.ln() * d.sqrt()).round() as u32).max(4),
+            min_size: 4,
+            history,
+            archive_rate,
+            p_max,
+        }
+    }
+}
+
+/// Memory of the successful F and CR values, the archive of replaced parents, and the F and CR of the current trials.
+#[derive(Tid)]
+pub struct JsoHistory {
+    memory_f: Vec<f64>,
+    /// `None` is the terminal value, after which CR is always 0.
+    memory_cr: Vec<Option<f64>>,
+    /// Next memory entry to be updated.
+    position: usize,
+    archive: Vec<Vec<f64>>,
+    /// F and CR of each trial vector of the current generation.
+    trial_parameters: Vec<(f64, f64)>,
+}
+
+impl CustomState<'_> for JsoHistory {}
+
+impl JsoHistory {
+    fn new(history: usize) -> Self {
+        let mut memory_f = vec![0.3; history];
+        let mut memory_cr = vec![Some(0.8); history];
+        memory_f[history - 1] = 0.9;
+        memory_cr[history - 1] = Some(0.9);
+        Self {
+            memory_f,
+            memory_cr,
+            position: 0,
+            archive: Vec::new(),
+            trial_parameters: Vec::new(),
+        }
+    }
+
+    /// Samples F and CR from a random memory entry, restricted early in the run; `progress` is the used fraction of
+    /// the evaluation budget.
+    fn sample_parameters<R: Rng + ?Sized>(&self, progress: f64, rng: &mut R) -> (f64, f64) {
+        let r = rng.gen_range(0..self.memory_f.len());
+        let cr = match self.memory_cr[r] {
+            None => 0.0,
+            Some(mean) => Normal::new(mean, 0.1).unwrap().sample(rng).clamp(0.0, 1.0),
+        };
+        let cauchy = Cauchy::new(self.memory_f[r], 0.1).unwrap();
+        let f = loop {
+            let f = cauchy.sample(rng);
+            if f > 0.0 {
+                break f.min(1.0);
+            }
+        };
+
+        let cr = match progress {
+            progress if progress < 0.25 => cr.max(0.7),
+            progress if progress < 0.5 => cr.max(0.6),
+            _ => cr,
+        };
+        let f = if progress < 0.6 { f.min(0.7) } else { f };
+        (f, cr)
+    }
+
+    /// Updates the next memory entry with the mean of its value and the weighted Lehmer means of the `successes`,
+    /// given as F, CR and improvement of the objective value. The last entry is never updated.
+    fn update_memory(&mut self, successes: &[(f64, f64, f64)]) {
+        if successes.is_empty() {
+            return;
+        }
+        let total: f64 = successes.iter().map(|&(_, _, improvement)| improvement).sum();
+        let weight = |improvement: f64| {
+            if total > 0.0 { improvement / total } else { 1.0 / successes.len() as f64 }
+        };
+        let lehmer_mean = |value: fn(&(f64, f64, f64)) -> f64| {
+            let squares: f64 = successes.iter().map(|s| weight(s.2) * value(s).powi(2)).sum();
+            let sum: f64 = successes.iter().map(|s| weight(s.2) * value(s)).sum();
+            squares / sum
+        };
+
+        let k = self.position;
+        let mean_f = lehmer_mean(|s| s.0);
+        let max_cr = successes.iter().map(|s| s.1).fold(0.0, f64::max);
+        let mean_cr = match self.memory_cr[k] {
+            Some(_) if max_cr > 0.0 => Some(lehmer_mean(|s| s.1)),
+            _ => None,
+        };
+
+        self.memory_f[k] = (mean_f + self.memory_f[k]) / 2.0;
+        self.memory_cr[k] = mean_cr.zip(self.memory_cr[k]).map(|(mean, old)| (mean + old) / 2.0);
+        self.position = (k + 1) % (self.memory_f.len() - 1);
+    }
+}
+
+/// Used fraction of the evaluation budget.
+fn progress<P: SingleObjectiveProblem>(state: &State<P>, evaluations: u32) -> f64 {
+    (state.evaluations() as f64 / evaluations as f64).min(1.0)
+}
+
+/// Creates a trial vector for each solution of the current population by current-to-pbest-w/1 mutation with a
+/// linearly decreasing p and binomial crossover, and pushes them as new population.
+///
+/// The archive is first reduced to `archive_rate` times the current population size by removing random solutions.
+#[derive(Clone, Serialize)]
+pub struct JsoVariation {
+    params: JsoParams,
+    /// Evaluation budget of the run.
+    evaluations: u32,
+}
+
+impl JsoVariation {
+    pub fn from_params(params: JsoParams, evaluations: u32) -> Self {
+        Self { params, evaluations }
+    }
+
+    pub fn new<P>(params: JsoParams, evaluations: u32) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(params, evaluations))
+    }
+}
+
+impl<P> Component<P> for JsoVariation
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.has::<JsoHistory>() {
+            state.insert(JsoHistory::new(self.params.history));
+        }
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let progress = progress(state, self.evaluations);
+        let populations = state.populations();
+        let parents = populations.current();
+        let mut history = state.borrow_mut::<JsoHistory>();
+        let mut rng = state.random_mut();
+
+        let n = parents.len();
+        let archive_size = (self.params.archive_rate * n as f64).round() as usize;
+        while history.archive.len() > archive_size {
+            let i = rng.gen_range(0..history.archive.len());
+            history.archive.swap_remove(i);
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| parents[a].objective().value().total_cmp(&parents[b].objective().value()));
+        let p = self.params.p_max * (1.0 - progress / 2.0);
+        let pbest_count = ((p * n as f64).round() as usize).clamp(2, n);
+
+        let mut trials = Vec::with_capacity(n);
+        let mut parameters = Vec::with_capacity(n);
+        for (i, parent) in parents.iter().enumerate() {
+            let (f, cr) = history.sample_parameters(progress, &mut *rng);
+            // weight of the difference to the pbest
+            let f_pbest = match progress {
+                progress if progress < 0.2 => 0.7 * f,
+                progress if progress < 0.4 => 0.8 * f,
+                _ => 1.2 * f,
+            };
+
+            // r1 from the population, r2 from the population and the archive, all distinct
+            let x = parent.solution();
+            let pbest = parents[order[rng.gen_range(0..pbest_count)]].solution();
+            let r1 = loop {
+                let r1 = rng.gen_range(0..n);
+                if r1 != i {
+                    break r1;
+                }
+            };
+            let r2 = loop {
+                let r2 = rng.gen_range(0..n + history.archive.len());
+                if r2 != i && r2 != r1 {
+                    break r2;
+                }
+            };
+            let x_r1 = parents[r1].solution();
+            let x_r2 = if r2 < n { parents[r2].solution() } else { &history.archive[r2 - n] };
+
+            let j_rand = rng.gen_range(0..x.len());
+            let trial = (0..x.len())
+                .map(|j| {
+                    if j == j_rand || rng.gen::<f64>() < cr {
+                        x[j] + f_pbest * (pbest[j] - x[j]) + f * (x_r1[j] - x_r2[j])
+                    } else {
+                        x[j]
+                    }
+                })
+                .collect();
+            trials.push(Individual::new_unevaluated(trial));
+            parameters.push((f, cr));
+        }
+
+        history.trial_parameters = parameters;
+        drop(history);
+        drop(rng);
+        drop(populations);
+        state.populations_mut().push(trials);
+        Ok(())
+    }
+}
+
+/// Replaces each parent by its evaluated trial vector if it is not worse, and updates the archive and the memory
+/// of F and CR.
+#[derive(Clone, Serialize)]
+pub struct JsoSelection;
+
+impl JsoSelection {
+    pub fn from_params() -> Self {
+        Self
+    }
+
+    pub fn new<P>() -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params())
+    }
+}
+
+impl<P> Component<P> for JsoSelection
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let trials = state.populations_mut().pop();
+        let mut populations = state.populations_mut();
+        let parents = populations.current_mut();
+        let mut history = state.borrow_mut::<JsoHistory>();
+
+        let parameters = std::mem::take(&mut history.trial_parameters);
+        let mut successes = Vec::new();
+        for ((parent, trial), (f, cr)) in parents.iter_mut().zip(trials).zip(parameters) {
+            let (parent_value, trial_value) = (parent.objective().value(), trial.objective().value());
+            if trial_value < parent_value {
+                history.archive.push(parent.solution().clone());
+                successes.push((f, cr, parent_value - trial_value));
+            }
+            if trial_value <= parent_value {
+                *parent = trial;
+            }
+        }
+        history.update_memory(&successes);
+        Ok(())
+    }
+}
+
+/// One jSO generation: current-to-pbest-w/1 mutation with archive, binomial crossover, evaluation, and the update
+/// of the population, the archive and the memory of F and CR.
+pub(crate) fn generation<P>(params: JsoParams, evaluations: u32, boundary: Boundary) -> Box<dyn Component<P>>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    Configuration::builder()
+        .do_(JsoVariation::new(params, evaluations))
+        .do_(boundary.operator())
+        .evaluate()
+        .update_best_individual()
+        .do_(JsoSelection::new())
+        .build_component()
+}
+
+/// jSO: L-SHADE with a memory of F and CR that starts at 0.3 and 0.8 and keeps a last entry fixed to 0.9, F and CR
+/// restricted early in the run, a weighted current-to-pbest-w/1 mutation and a linearly decreasing p.
+///
+/// Solutions outside the domain are corrected according to the `boundary` handling instead of the midpoint
+/// between the parent and the violated bound of the original algorithm, to keep the comparison with the other
+/// algorithms fair.
+pub fn jso<P>(
+    evaluations: u32,
+    params: JsoParams,
+    boundary: Boundary,
+    initialization: Initialization,
+    diversity: &[DiversityMeasure],
+) -> Configuration<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    Configuration::builder()
+        .do_(initialization.operator(params.initial_size))
+        .update_best_individual()
+        .do_(diversity_measures(diversity))
+        .do_(Logger::new())
+        .while_(
+            conditions::LessThanN::evaluations(evaluations),
+            |builder| {
+                builder
+                    .do_(generation(params, evaluations, boundary))
+                    .do_(LinearPopulationReduction::new(params.initial_size, params.min_size, evaluations))
+                    .do_(diversity_measures(diversity))
+                    .do_(Logger::new())
+            }
+        )
+        .build()
+}
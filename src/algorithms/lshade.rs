@@ -0,0 +1,172 @@
+use mahf::{prelude::*,
+           configuration::Configuration, logging::Logger, state::StateReq,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+use serde::Serialize;
+
+use super::diversity::diversity_measures;
+use super::parameters::{Boundary, Crossover, DiversityMeasure, Initialization};
+use super::shade::generation;
+
+/// Parameters of L-SHADE.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct LshadeParams {
+    /// Population size at the start, reduced linearly to `min_size` over the evaluation budget.
+    pub initial_size: u32,
+    pub min_size: u32,
+    /// Number of entries of the memory of F and CR.
+    pub history: usize,
+    /// Size of the archive relative to the initial population size.
+    pub archive_rate: f64,
+    /// Minimum fraction of the best solutions the pbest is chosen from; the fraction is drawn up to 0.2 as in SHADE.
+    pub p_min: f64,
+}
+
+impl LshadeParams {
+    /// L-SHADE (Tanabe and Fukunaga 2014) with an initial population of `population_factor` times the number of
+    /// `dimensions`.
+    pub fn lshade(dimensions: usize, population_factor: f64, history: usize, archive_rate: f64, p_min: f64) -> Self {
+        Self {
+            initial_size: ((population_factor * dimensions as f64).round() as u32).max(4),
+            min_size: 4,
+            history,
+            archive_rate,
+            p_min,
+        }
+    }
+
+    /// Maximum size of the archive.
+    fn max_archive(&self) -> usize {
+        (self.archive_rate * self.initial_size as f64).round() as usize
+    }
+}
+
+/// Reduces the population size linearly from `initial_size` to `min_size` over the evaluation budget by removing
+/// the worst solutions.
+#[derive(Clone, Serialize)]
+pub struct LinearPopulationReduction {
+    initial_size: u32,
+    min_size: u32,
+    /// Evaluation budget of the run.
+    evaluations: u32,
+}
+
+impl LinearPopulationReduction {
+    pub fn from_params(initial_size: u32, min_size: u32, evaluations: u32) -> Self {
+        Self { initial_size, min_size, evaluations }
+    }
+
+    pub fn new<P: SingleObjectiveProblem>(initial_size: u32, min_size: u32, evaluations: u32) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(initial_size, min_size, evaluations))
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for LinearPopulationReduction {
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let progress = (state.evaluations() as f64 / self.evaluations as f64).min(1.0);
+        let (initial, min) = (self.initial_size as f64, self.min_size as f64);
+        let size = ((initial + (min - initial) * progress).round() as usize).max(self.min_size as usize);
+
+        let mut populations = state.populations_mut();
+        let population = populations.current_mut();
+        if size < population.len() {
+            population.sort_by(|a, b| a.objective().value().total_cmp(&b.objective().value()));
+            population.truncate(size);
+        }
+        Ok(())
+    }
+}
+
+/// SHADE generation with binomial crossover for the current population size, see [`generation`].
+///
+/// The current-to-pbest selection of SHADE creates offspring for a fixed population size, so the generation is
+/// built for each execution with the size of the current population. Its state does not depend on the population
+/// size, so it is initialised once with the initial population size.
+#[derive(Clone, Serialize)]
+pub struct ReducedGeneration {
+    params: LshadeParams,
+    boundary: Boundary,
+}
+
+impl ReducedGeneration {
+    pub fn from_params(params: LshadeParams, boundary: Boundary) -> Self {
+        Self { params, boundary }
+    }
+
+    pub fn new<P>(params: LshadeParams, boundary: Boundary) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(params, boundary))
+    }
+
+    /// The SHADE generation for a population of `population_size` solutions.
+    fn with_size<P>(&self, population_size: u32) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        // initial values of F and CR; of no consequence when using SHADEAdaptation
+        let (f, cr) = (0.5, 0.5);
+        generation(
+            population_size,
+            1, // current-to-pbest/1
+            self.params.p_min,
+            self.params.max_archive(),
+            f,
+            Crossover::Bin.operator(cr),
+            self.boundary,
+        )
+    }
+}
+
+impl<P> Component<P> for ReducedGeneration
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        self.with_size::<P>(self.params.initial_size).init(problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        self.with_size::<P>(self.params.initial_size).require(problem, state_req)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let population_size = state.populations().current().len() as u32;
+        self.with_size::<P>(population_size).execute(problem, state)
+    }
+}
+
+/// L-SHADE: SHADE with binomial crossover whose population is reduced linearly over the evaluation budget.
+///
+/// The generations are those of [`shade`](super::shade::shade), built from the SHADE components of MAHF, so the
+/// memory of F and CR starts at 0.5 and the archive keeps its initial maximum size. Unlike the fixed p of the
+/// original L-SHADE, the pbest fraction is drawn from `p_min` to 0.2 for each solution as in SHADE, since the
+/// current-to-pbest selection of MAHF is used as is.
+///
+/// Solutions outside the domain are corrected according to the `boundary` handling instead of the midpoint
+/// between the parent and the violated bound of the original algorithm, to keep the comparison with the other
+/// algorithms fair.
+pub fn lshade<P>(
+    evaluations: u32,
+    params: LshadeParams,
+    boundary: Boundary,
+    initialization: Initialization,
+    diversity: &[DiversityMeasure],
+) -> Configuration<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    Configuration::builder()
+        .do_(initialization.operator(params.initial_size))
+        .update_best_individual()
+        .do_(diversity_measures(diversity))
+        .do_(mapping::de::SHADEAdaptationInit::new(params.history).expect("failed to initialise SHADE adaptation states"))
+        .do_(Logger::new())
+        .while_(
+            conditions::LessThanN::evaluations(evaluations),
+            |builder| {
+                builder
+                    .do_(ReducedGeneration::new(params, boundary))
+                    .do_(LinearPopulationReduction::new(params.initial_size, params.min_size, evaluations))
+                    .do_(diversity_measures(diversity))
+                    .do_(Logger::new())
+            }
+        )
+        .build()
+}
@@ -1,7 +1,18 @@
 pub mod pso;
-pub mod pso_npgm;
-pub mod pso_srm;
-pub mod pso_gpgm;
-pub mod pso_rr;
-pub mod pso_pdm;
-pub mod shade;
\ No newline at end of file
+pub mod exploration_pso;
+pub mod adaptation;
+pub mod parameters;
+pub mod triggers;
+pub mod shade;
+pub mod portfolio;
+pub mod replacement;
+pub mod restart;
+pub mod topology;
+pub mod velocity;
+pub mod bounds;
+pub mod sampling;
+pub mod events;
+pub mod diversity;
+pub mod lshade;
+pub mod jso;
+pub mod cmaes;
\ No newline at end of file
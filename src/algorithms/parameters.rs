@@ -0,0 +1,688 @@
+use std::fmt;
+use std::ops::Range;
+
+use anyhow::bail;
+use clap::{ArgAction, Args, ValueEnum};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use mahf::{prelude::*,
+           components::initialization,
+           configuration::Configuration,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+
+use super::bounds::BoundaryHandler;
+use super::exploration_pso::{BaseAlgorithm, NewPopAdaptationParams, ParticlePolicy};
+use super::portfolio::CreditAssignment;
+use super::replacement::{ReplaceN, ReplaceNParticles};
+use super::restart::RestartParams;
+use super::sampling::{OppositionSelection, SampledInit};
+use super::velocity::{NeighbourhoodVelocitiesUpdate, UpdateRule, VelocityLimit};
+use super::triggers::{Cooldown, Trigger};
+
+/// Implements `Display`, `Serialize` and `Deserialize` using the name of the `ValueEnum` variant, i.e. the value
+/// accepted on the command line.
+macro_rules! value_enum_names {
+    ($($name:ty),*) => {
+        $(
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    self.to_possible_value()
+                        .expect("no values are skipped")
+                        .get_name()
+                        .fmt(f)
+                }
+            }
+
+            impl Serialize for $name {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let name = String::deserialize(deserializer)?;
+                    <$name as ValueEnum>::from_str(&name, false).map_err(D::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+/// Condition that triggers the exploration mechanism.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Exploration {
+    /// Best objective value stagnated for a fraction of the evaluation budget.
+    Evaluations,
+    /// Normalized diversity, measured by `--diversity-measure`, dropped below a threshold.
+    Diversity,
+    /// Composite condition given by `--trigger`.
+    Combined,
+}
+
+/// Parameters of the condition triggering the exploration mechanism.
+#[derive(Args, Serialize)]
+pub struct ExplorationArgs {
+    /// Exploration condition; evaluations, diversity or combined
+    #[arg(long, value_enum, default_value_t = Exploration::Evaluations)]
+    pub exploration: Exploration,
+
+    /// Exploration parameter
+    #[arg(long, default_value_t = 0.05)]
+    pub exp_param: f64,
+
+    /// Diversity measure of the diversity exploration condition; minimum_distance, dimension_wise, pairwise,
+    /// distance_to_centroid, radius or true_diversity
+    #[arg(long, value_enum, default_value_t = DiversityMeasure::MinimumDistance)]
+    pub diversity_measure: DiversityMeasure,
+
+    /// Composite exploration condition for combined exploration, e.g. "or(stagnation(0.05),diversity(0.1))";
+    /// stagnation(x), diversity(x), diversity(x,measure), and(..), or(..) and not(..) can be nested
+    #[arg(long, required_if_eq("exploration", "combined"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<Trigger>,
+
+    /// Minimum gap after the exploration mechanism was triggered before it can be triggered again; 0 to disable
+    #[arg(long, default_value_t = 0)]
+    pub cooldown: u32,
+
+    /// Unit of the cooldown gap; iterations or evaluations
+    #[arg(long, value_enum, default_value_t = CooldownUnit::Iterations)]
+    pub cooldown_unit: CooldownUnit,
+
+    /// Number of iterations after an activation in which a new global best at an index replaced by the mechanism
+    /// counts as its success
+    #[arg(long, default_value_t = 10)]
+    pub attribution_window: u32,
+}
+
+impl ExplorationArgs {
+    pub fn trigger(&self) -> Trigger {
+        match self.exploration {
+            Exploration::Evaluations => Trigger::Stagnation(self.exp_param),
+            Exploration::Diversity => Trigger::Diversity(self.exp_param, self.diversity_measure),
+            Exploration::Combined => self.trigger.clone().expect("--trigger is required for combined exploration"),
+        }
+    }
+
+    /// Builds the condition; `evaluations` is the evaluation budget the stagnation fraction refers to.
+    pub fn condition<P>(&self, evaluations: u32) -> Box<dyn Condition<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        let condition = self.trigger().condition(evaluations);
+        if self.cooldown > 0 {
+            Cooldown::new(condition, self.cooldown, self.cooldown_unit)
+        } else {
+            condition
+        }
+    }
+
+    pub fn description(&self) -> String {
+        let description = match self.exploration {
+            Exploration::Combined => format!("{}_{}", self.exploration, self.trigger()),
+            Exploration::Diversity if self.diversity_measure != DiversityMeasure::MinimumDistance => {
+                format!("{}_{}_{}", self.exploration, self.exp_param, self.diversity_measure)
+            }
+            _ => format!("{}_{}", self.exploration, self.exp_param),
+        };
+        if self.cooldown > 0 {
+            format!("{}_cooldown{}{}", description, self.cooldown, self.cooldown_unit)
+        } else {
+            description
+        }
+    }
+}
+
+/// Parameters of the adaptation of the number of solutions replaced by the exploration mechanism.
+#[derive(Args, Serialize)]
+pub struct NewPopAdaptationArgs {
+    /// Adapt the number of replaced solutions, starting from new_pop, depending on whether the exploration
+    /// mechanism leads to a new global best
+    #[arg(long, action = ArgAction::Set, default_value_t = false)]
+    pub adaptive_new_pop: bool,
+
+    /// Number of iterations after an activation in which a new global best counts as success
+    #[arg(long, default_value_t = 10)]
+    pub adaptation_window: u32,
+
+    /// Change of the number of replaced solutions after each activation
+    #[arg(long, default_value_t = 5)]
+    pub adaptation_step: u32,
+}
+
+/// Checks that the exploration mechanism replaces between 1 and `population_size` solutions.
+pub fn check_new_pop(new_pop: u32, population_size: u32) -> anyhow::Result<()> {
+    if !(1..=population_size).contains(&new_pop) {
+        bail!("new_pop must be between 1 and the population size {}, but is {}", population_size, new_pop);
+    }
+    Ok(())
+}
+
+impl NewPopAdaptationArgs {
+    pub fn params(&self) -> Option<NewPopAdaptationParams> {
+        self.adaptive_new_pop.then_some(NewPopAdaptationParams {
+            window: self.adaptation_window,
+            step: self.adaptation_step,
+        })
+    }
+
+    /// Suffix for the description of the algorithm; empty without adaptation.
+    pub fn description(&self) -> String {
+        if self.adaptive_new_pop {
+            format!("_adaptive{}_{}", self.adaptation_window, self.adaptation_step)
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// Handling of the particles replaced by an exploration mechanism.
+#[derive(Args, Serialize)]
+pub struct ParticlePolicyArgs {
+    /// Personal best of replaced particles; keep, reset or keep_better
+    #[arg(long, value_enum, default_value_t = PersonalBestPolicy::KeepBetter)]
+    pub pbest_policy: PersonalBestPolicy,
+
+    /// Velocity of replaced particles; zero, random or inherit
+    #[arg(long, value_enum, default_value_t = VelocityPolicy::Random)]
+    pub velocity_policy: VelocityPolicy,
+}
+
+impl ParticlePolicyArgs {
+    pub fn policy(&self) -> ParticlePolicy {
+        ParticlePolicy {
+            personal_best: self.pbest_policy,
+            velocity: self.velocity_policy,
+        }
+    }
+
+    /// Suffix for the description of the algorithm; empty for the default policies.
+    pub fn description(&self) -> String {
+        if self.policy() == ParticlePolicy::default() {
+            String::new()
+        } else {
+            format!("_pbest_{}_velocity_{}", self.pbest_policy, self.velocity_policy)
+        }
+    }
+}
+
+/// Parameters of random restarts.
+#[derive(Args, Serialize)]
+pub struct RestartArgs {
+    /// Number of particles kept on restart, i.e. the global best and the particles with the best personal bests
+    /// (the best solutions, for SHADE); 0 restarts the whole population
+    #[arg(long, default_value_t = 0)]
+    pub restart_keep: u32,
+
+    /// Fraction of the solutions that are not kept which is re-initialised on restart; 0.0 to 1.0
+    #[arg(long, default_value_t = 1.0)]
+    pub restart_fraction: f64,
+
+    /// Factor by which the population grows on each restart (IPOP-style), only for PSO; 1.0 keeps the population
+    /// size
+    #[arg(long, default_value_t = 1.0)]
+    pub restart_growth: f64,
+}
+
+impl RestartArgs {
+    pub fn params(&self) -> RestartParams {
+        RestartParams {
+            keep: self.restart_keep,
+            fraction: self.restart_fraction,
+            growth: self.restart_growth,
+        }
+    }
+
+    /// Parameter values joined by `_`; empty for full restarts, which were the only mode before.
+    pub fn description(&self) -> String {
+        if self.params() == RestartParams::default() {
+            String::new()
+        } else {
+            format!("restart{}_{}_{}", self.restart_keep, self.restart_fraction, self.restart_growth)
+        }
+    }
+}
+
+/// Parameters of the credit assignment of the mechanism portfolio.
+#[derive(Args, Serialize)]
+pub struct PortfolioArgs {
+    /// Credit assignment rule selecting the mechanism; ucb or probability_matching
+    #[arg(long, value_enum, default_value_t = Credit::Ucb)]
+    pub credit: Credit,
+
+    /// Weight of the exploration term of UCB
+    #[arg(long, default_value_t = 1.0)]
+    pub ucb_c: f64,
+
+    /// Adaptation rate of the mechanism qualities for probability matching; 0.0 to 1.0
+    #[arg(long, default_value_t = 0.3)]
+    pub pm_alpha: f64,
+
+    /// Minimum selection probability of each mechanism for probability matching; 0.0 to 1 / number of mechanisms
+    #[arg(long, default_value_t = 0.05)]
+    pub pm_p_min: f64,
+}
+
+impl PortfolioArgs {
+    pub fn credit_assignment(&self) -> CreditAssignment {
+        match self.credit {
+            Credit::Ucb => CreditAssignment::Ucb { c: self.ucb_c },
+            Credit::ProbabilityMatching => CreditAssignment::ProbabilityMatching {
+                alpha: self.pm_alpha,
+                p_min: self.pm_p_min,
+            },
+        }
+    }
+
+    /// Checks that the minimum selection probability of probability matching is possible for `arms` mechanisms.
+    pub fn check_arms(&self, arms: usize) -> anyhow::Result<()> {
+        if self.credit == Credit::ProbabilityMatching && self.pm_p_min * arms as f64 > 1.0 {
+            bail!(
+                "the minimum selection probability of {} mechanisms must be at most 1 / {}, but is {}",
+                arms,
+                arms,
+                self.pm_p_min
+            );
+        }
+        Ok(())
+    }
+
+    pub fn description(&self) -> String {
+        match self.credit {
+            Credit::Ucb => format!("{}_{}", self.credit, self.ucb_c),
+            Credit::ProbabilityMatching => format!("{}_{}_{}", self.credit, self.pm_alpha, self.pm_p_min),
+        }
+    }
+}
+
+/// Credit assignment rule of the mechanism portfolio.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Credit {
+    Ucb,
+    #[value(name = "probability_matching")]
+    ProbabilityMatching,
+}
+
+/// Unit of the gap between two activations of the exploration mechanism.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CooldownUnit {
+    Iterations,
+    Evaluations,
+}
+
+/// Solutions of the swarm that are replaced by the solutions of the exploration mechanism.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Replacement {
+    Best,
+    Worst,
+    Random,
+}
+
+impl Replacement {
+    pub fn operator<P>(self, new_pop: u32, base: &BaseAlgorithm) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        match base {
+            BaseAlgorithm::Pso { v_max, policy } => {
+                ReplaceNParticles::new(new_pop, self, policy.velocity, v_max.clone())
+            }
+            BaseAlgorithm::De => ReplaceN::new(new_pop, self),
+        }
+    }
+}
+
+/// Neighbourhood topology of PSO, determining the particles whose personal bests attract a particle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Topology {
+    /// All particles, i.e. the global best.
+    Global,
+    /// The two adjacent particles (lbest).
+    Ring,
+    /// The four adjacent particles on a wrapped grid.
+    #[value(name = "von_neumann")]
+    VonNeumann,
+    /// Random informants, redrawn after each iteration without improvement.
+    Random,
+    /// A ring with two neighbours on each side whose links are randomly rewired.
+    #[value(name = "small_world")]
+    SmallWorld,
+}
+
+impl Topology {
+    /// Velocity update with the coefficients of the `rule`; `evaluations` is the budget time-varying rules refer to.
+    pub fn velocity_update<P>(self, rule: UpdateRule, v_max: &VelocityLimit, evaluations: u32) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        match (self, rule, v_max.uniform()) {
+            (Topology::Global, UpdateRule::Constant { w, c1, c2 }, Some(v_max)) => {
+                Box::from(swarm::pso::ParticleVelocitiesUpdate::new(w, c1, c2, v_max))
+            }
+            _ => NeighbourhoodVelocitiesUpdate::new(rule, v_max.clone(), self, evaluations),
+        }
+    }
+}
+
+/// Rule for the inertia weight and acceleration coefficients of PSO, see [`UpdateRule`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Rule {
+    Constant,
+    #[value(name = "linear_inertia")]
+    LinearInertia,
+    #[value(name = "nonlinear_inertia")]
+    NonlinearInertia,
+    Tvac,
+    Constriction,
+}
+
+/// Parameters of the rule for the inertia weight and acceleration coefficients of PSO.
+#[derive(Args, Serialize)]
+pub struct UpdateRuleArgs {
+    /// Rule for inertia weight and acceleration coefficients; constant, linear_inertia, nonlinear_inertia, tvac
+    /// or constriction
+    #[arg(long, value_enum, default_value_t = Rule::Constant)]
+    pub update_rule: Rule,
+
+    /// Initial inertia weight of time-varying rules
+    #[arg(long, default_value_t = 0.9)]
+    pub w_start: f64,
+
+    /// Final inertia weight of time-varying rules
+    #[arg(long, default_value_t = 0.4)]
+    pub w_end: f64,
+
+    /// Exponent of the nonlinearly decreasing inertia weight
+    #[arg(long, default_value_t = 2.0)]
+    pub inertia_exponent: f64,
+
+    /// Initial C1 of TVAC
+    #[arg(long, default_value_t = 2.5)]
+    pub c1_start: f64,
+
+    /// Final C1 of TVAC
+    #[arg(long, default_value_t = 0.5)]
+    pub c1_end: f64,
+
+    /// Initial C2 of TVAC
+    #[arg(long, default_value_t = 0.5)]
+    pub c2_start: f64,
+
+    /// Final C2 of TVAC
+    #[arg(long, default_value_t = 2.5)]
+    pub c2_end: f64,
+
+    /// Cognitive coefficient phi1 of constriction; phi1 + phi2 > 4
+    #[arg(long, default_value_t = 2.05)]
+    pub phi1: f64,
+
+    /// Social coefficient phi2 of constriction; phi1 + phi2 > 4
+    #[arg(long, default_value_t = 2.05)]
+    pub phi2: f64,
+}
+
+impl UpdateRuleArgs {
+    /// Builds the rule; `w`, `c1` and `c2` are used where the rule does not vary them.
+    ///
+    /// Fails for constriction with `phi1 + phi2 <= 4`, for which the constriction coefficient is not defined.
+    pub fn rule(&self, w: f64, c1: f64, c2: f64) -> anyhow::Result<UpdateRule> {
+        let rule = match self.update_rule {
+            Rule::Constant => UpdateRule::Constant { w, c1, c2 },
+            Rule::LinearInertia => UpdateRule::LinearInertia { w_start: self.w_start, w_end: self.w_end, c1, c2 },
+            Rule::NonlinearInertia => UpdateRule::NonlinearInertia {
+                w_start: self.w_start,
+                w_end: self.w_end,
+                exponent: self.inertia_exponent,
+                c1,
+                c2,
+            },
+            Rule::Tvac => UpdateRule::Tvac {
+                w_start: self.w_start,
+                w_end: self.w_end,
+                c1_start: self.c1_start,
+                c1_end: self.c1_end,
+                c2_start: self.c2_start,
+                c2_end: self.c2_end,
+            },
+            Rule::Constriction => {
+                if self.phi1 + self.phi2 <= 4.0 {
+                    bail!(
+                        "constriction requires phi1 + phi2 > 4, but phi1 = {} and phi2 = {}",
+                        self.phi1,
+                        self.phi2
+                    );
+                }
+                UpdateRule::Constriction { phi1: self.phi1, phi2: self.phi2 }
+            }
+        };
+        Ok(rule)
+    }
+
+    /// Suffix for the description of the algorithm; empty for constant coefficients.
+    pub fn description(&self) -> String {
+        match self.update_rule {
+            Rule::Constant => String::new(),
+            Rule::LinearInertia => format!("_{}_{}_{}", self.update_rule, self.w_start, self.w_end),
+            Rule::NonlinearInertia => {
+                format!("_{}_{}_{}_{}", self.update_rule, self.w_start, self.w_end, self.inertia_exponent)
+            }
+            Rule::Tvac => format!(
+                "_{}_{}_{}_{}_{}_{}_{}",
+                self.update_rule, self.w_start, self.w_end, self.c1_start, self.c1_end, self.c2_start, self.c2_end,
+            ),
+            Rule::Constriction => format!("_{}_{}_{}", self.update_rule, self.phi1, self.phi2),
+        }
+    }
+}
+
+/// Personal best of a particle replaced by an exploration mechanism.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PersonalBestPolicy {
+    /// The previous personal best is kept, even if the new position is better.
+    Keep,
+    /// The personal best is reset to the new position, even if it is worse.
+    Reset,
+    /// The better of the previous personal best and the new position is kept.
+    #[value(name = "keep_better")]
+    KeepBetter,
+}
+
+/// Velocity of a particle replaced by an exploration mechanism.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum VelocityPolicy {
+    Zero,
+    /// Uniformly random within `[-v_max, v_max]`.
+    Random,
+    /// The velocity of the replaced particle is kept.
+    Inherit,
+}
+
+/// Solution that provides the basis for generating new solutions, i.e. the center of SRM or the leader of PDM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ReferenceSolution {
+    Best,
+    #[value(name = "random_new")]
+    RandomNew,
+    #[value(name = "random_solution")]
+    RandomSolution,
+}
+
+/// Termination criterion of the nuclear reaction mechanism of GPGM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TerminationType {
+    Iterations,
+    Evaluations,
+}
+
+/// Crossover operator of SHADE.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Crossover {
+    Bin,
+    Exp,
+}
+
+impl Crossover {
+    pub fn operator<P>(self, cr: f64) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        match self {
+            Crossover::Bin => recombination::de::DEBinomialCrossover::new(cr).unwrap(),
+            Crossover::Exp => recombination::de::DEExponentialCrossover::new(cr).unwrap(),
+        }
+    }
+}
+
+/// Measure of the diversity of the population, normalized by its largest value in the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DiversityMeasure {
+    /// Smallest distance between two solutions.
+    #[value(name = "minimum_distance")]
+    MinimumDistance,
+    /// Mean absolute deviation from the median in each dimension, averaged over the dimensions.
+    #[value(name = "dimension_wise")]
+    DimensionWise,
+    /// Mean distance between all pairs of solutions.
+    Pairwise,
+    /// Mean distance of the solutions to their centroid.
+    #[value(name = "distance_to_centroid")]
+    DistanceToCentroid,
+    /// Largest distance of a solution to the centroid.
+    Radius,
+    /// Square root of the sum of the variances of all dimensions.
+    #[value(name = "true_diversity")]
+    TrueDiversity,
+}
+
+/// Handling of coordinates outside the domain after the velocity update, the crossover of SHADE and the
+/// exploration mechanisms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Boundary {
+    /// Set to the violated bound.
+    Clamp,
+    /// Mirrored at the violated bound.
+    Reflect,
+    /// Wrapped around to the opposite bound, i.e. a toroidal domain.
+    Periodic,
+    /// Uniformly random within the domain.
+    Random,
+    /// Set to the midpoint between the violated bound and the best solution.
+    Midpoint,
+    /// Cosine correction of MAHF.
+    Cosine,
+}
+
+impl Boundary {
+    pub fn operator<P>(self) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        BoundaryHandler::new(self)
+    }
+}
+
+/// Parameters of the boundary handling, shared by all algorithms.
+#[derive(Args, Serialize)]
+pub struct BoundaryArgs {
+    /// Handling of solutions outside the domain; clamp, reflect, periodic, random, midpoint or cosine
+    #[arg(long, value_enum, default_value_t = Boundary::Cosine)]
+    pub boundary: Boundary,
+}
+
+impl BoundaryArgs {
+    /// Suffix for the description of the algorithm; empty for the default cosine correction.
+    pub fn description(&self) -> String {
+        match self.boundary {
+            Boundary::Cosine => String::new(),
+            boundary => format!("_{}", boundary),
+        }
+    }
+}
+
+/// Velocity limit and neighbourhood topology, shared by all PSO variants.
+#[derive(Args, Serialize)]
+pub struct SwarmArgs {
+    /// Velocity limit of PSO as fraction of the domain width in each dimension; tuning in 0.05 to 1.0
+    #[arg(long, default_value_t = 0.5)]
+    pub v_max_fraction: f64,
+
+    /// Neighbourhood topology; global, ring, von_neumann, random or small_world
+    #[arg(long, value_enum, default_value_t = Topology::Global)]
+    pub topology: Topology,
+}
+
+impl SwarmArgs {
+    pub fn v_max(&self, domain: &[Range<f64>]) -> VelocityLimit {
+        VelocityLimit::from_domain(self.v_max_fraction, domain)
+    }
+
+    /// Suffix for the description of the algorithm; empty for the default limit of 0.5 and the global topology.
+    pub fn description(&self) -> String {
+        let description = match self.v_max_fraction {
+            fraction if fraction == 0.5 => String::new(),
+            fraction => format!("_vmax{}", fraction),
+        };
+        match self.topology {
+            Topology::Global => description,
+            topology => format!("{}_{}", description, topology),
+        }
+    }
+}
+
+/// Sampling of the initial population and of the solutions re-initialised by random restarts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Initialization {
+    /// Uniformly random within the domain.
+    Random,
+    /// Latin hypercube sampling.
+    Lhs,
+    /// Scrambled Sobol sequence; up to 256 dimensions.
+    Sobol,
+    /// Randomly shifted Halton sequence.
+    Halton,
+    /// Opposition-based initialisation; of each random solution and its opposite, the better one is kept,
+    /// which costs twice the evaluations.
+    Opposition,
+}
+
+impl Initialization {
+    /// Creates and evaluates the initial population.
+    pub fn operator<P>(self, population_size: u32) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        let builder = match self {
+            Initialization::Random => Configuration::builder().do_(initialization::RandomSpread::new(population_size)),
+            initialization => Configuration::builder().do_(SampledInit::new(initialization, population_size)),
+        };
+        builder
+            .evaluate()
+            .do_if_some_((self == Initialization::Opposition).then(OppositionSelection::new))
+            .build_component()
+    }
+}
+
+/// Parameters of the initialisation, shared by all algorithms with an initial population.
+#[derive(Args, Serialize)]
+pub struct InitializationArgs {
+    /// Initial population, also used for the solutions re-initialised by random restarts; random, lhs, sobol,
+    /// halton or opposition
+    #[arg(long, value_enum, default_value_t = Initialization::Random)]
+    pub initialization: Initialization,
+}
+
+impl InitializationArgs {
+    /// Suffix for the description of the algorithm; empty for the default uniform random initialisation.
+    pub fn description(&self) -> String {
+        match self.initialization {
+            Initialization::Random => String::new(),
+            initialization => format!("_{}", initialization),
+        }
+    }
+}
+
+/// Restart strategy of CMA-ES.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RestartStrategy {
+    /// Population size doubled on each restart (Auger and Hansen 2005).
+    Ipop,
+    /// Alternating between doubled population sizes and small population sizes with smaller step sizes,
+    /// balancing the evaluations spent in both regimes (Hansen 2009).
+    Bipop,
+}
+
+value_enum_names!(Exploration, Credit, CooldownUnit, Replacement, Topology, Rule, PersonalBestPolicy, VelocityPolicy, ReferenceSolution, TerminationType, Crossover, DiversityMeasure, Boundary, Initialization, RestartStrategy);
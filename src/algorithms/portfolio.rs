@@ -0,0 +1,149 @@
+use better_any::{Tid, TidAble};
+use derive_more::{Deref, DerefMut};
+use mahf::{prelude::*, state::StateReq, CustomState,
+           problems::SingleObjectiveProblem};
+use rand::Rng;
+use serde::Serialize;
+
+use super::events::MechanismEvents;
+
+/// Credit assignment rule selecting the arm of the [`MechanismPortfolio`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum CreditAssignment {
+    /// Upper confidence bound; `c` weights the exploration term.
+    Ucb { c: f64 },
+    /// Probability matching with adaptation rate `alpha` and minimum selection probability `p_min`.
+    ProbabilityMatching { alpha: f64, p_min: f64 },
+}
+
+/// Number of times each arm of the portfolio was selected.
+#[derive(Default, Clone, Serialize, Deref, DerefMut, Tid)]
+pub struct ArmSelections(pub Vec<u32>);
+
+impl CustomState<'_> for ArmSelections {}
+
+/// Sum of the rewards of each arm of the portfolio; an activation is rewarded with 1 if it led to a new global best.
+#[derive(Default, Clone, Serialize, Deref, DerefMut, Tid)]
+pub struct ArmRewards(pub Vec<f64>);
+
+impl CustomState<'_> for ArmRewards {}
+
+/// Estimated quality of each arm for probability matching.
+#[derive(Default, Tid)]
+struct ArmQualities(Vec<f64>);
+
+impl CustomState<'_> for ArmQualities {}
+
+/// Selects one of the exploration mechanisms (arms) each time it is executed, using a credit assignment rule.
+///
+/// Each arm has to evaluate the solutions it generates and update the best individual, such that its reward
+/// is known directly after its execution, and record its activation as `MechanismEvent`, which is annotated with
+/// the index of the arm and its reward.
+#[derive(Clone, Serialize)]
+#[serde(bound = "")]
+pub struct MechanismPortfolio<P: Problem> {
+    arms: Vec<Box<dyn Component<P>>>,
+    credit: CreditAssignment,
+}
+
+impl<P: SingleObjectiveProblem> MechanismPortfolio<P> {
+    pub fn from_params(arms: Vec<Box<dyn Component<P>>>, credit: CreditAssignment) -> Self {
+        assert!(!arms.is_empty(), "the portfolio needs at least one arm");
+        Self { arms, credit }
+    }
+
+    pub fn new(arms: Vec<Box<dyn Component<P>>>, credit: CreditAssignment) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(arms, credit))
+    }
+
+    fn select(&self, state: &mut State<P>) -> usize {
+        let k = self.arms.len();
+        match self.credit {
+            CreditAssignment::Ucb { c } => {
+                let selections = state.borrow::<ArmSelections>().0.clone();
+                if let Some(untried) = selections.iter().position(|&n| n == 0) {
+                    return untried;
+                }
+                let rewards = state.borrow::<ArmRewards>();
+                let total: u32 = selections.iter().sum();
+                (0..k)
+                    .map(|i| {
+                        let n = selections[i] as f64;
+                        rewards[i] / n + c * (2.0 * (total as f64).ln() / n).sqrt()
+                    })
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                    .map(|(i, _)| i)
+                    .unwrap()
+            }
+            CreditAssignment::ProbabilityMatching { p_min, .. } => {
+                let qualities = state.borrow::<ArmQualities>().0.clone();
+                let sum: f64 = qualities.iter().sum();
+                let probabilities: Vec<f64> = qualities
+                    .iter()
+                    .map(|q| {
+                        if sum > 0.0 {
+                            p_min + (1.0 - k as f64 * p_min) * q / sum
+                        } else {
+                            1.0 / k as f64
+                        }
+                    })
+                    .collect();
+
+                let mut r = state.random_mut().gen::<f64>() * probabilities.iter().sum::<f64>();
+                for (i, p) in probabilities.iter().enumerate() {
+                    if r < *p {
+                        return i;
+                    }
+                    r -= p;
+                }
+                k - 1
+            }
+        }
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for MechanismPortfolio<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        for arm in &self.arms {
+            arm.init(problem, state)?;
+        }
+        let k = self.arms.len();
+        state.insert(ArmSelections(vec![0; k]));
+        state.insert(ArmRewards(vec![0.0; k]));
+        state.insert(ArmQualities(vec![0.0; k]));
+        Ok(())
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        for arm in &self.arms {
+            arm.require(problem, state_req)?;
+        }
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let arm = self.select(state);
+
+        let best_before = state.best_objective_value();
+        self.arms[arm].execute(problem, state)?;
+        let best_after = state.best_objective_value();
+
+        let reward = match (best_after, best_before) {
+            (Some(after), Some(before)) if after < before => 1.0,
+            _ => 0.0,
+        };
+
+        if let Some(event) = state.borrow_mut::<MechanismEvents>().0.last_mut() {
+            event.arm = Some(arm);
+            event.reward = Some(reward);
+        }
+        state.borrow_mut::<ArmSelections>()[arm] += 1;
+        state.borrow_mut::<ArmRewards>()[arm] += reward;
+        if let CreditAssignment::ProbabilityMatching { alpha, .. } = self.credit {
+            let mut qualities = state.borrow_mut::<ArmQualities>();
+            qualities.0[arm] += alpha * (reward - qualities.0[arm]);
+        }
+        Ok(())
+    }
+}
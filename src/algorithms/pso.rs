@@ -1,41 +1,39 @@
 use mahf::{prelude::*,
-           components::{initialization,
-                        measures::{diversity::{MinimumIndividualDistance}, }},
            configuration::Configuration, logging::Logger,
            problems::{LimitedVectorProblem, SingleObjectiveProblem}};
 
+use super::diversity::diversity_measures;
+use super::parameters::{Boundary, DiversityMeasure, Initialization, Topology};
+use super::velocity::{UpdateRule, VelocityLimit};
+
 
 pub fn basic_pso<P>(
     evaluations: u32,
     population_size: u32,
-    w: f64,
-    c1: f64,
-    c2: f64,
-    v_max: f64,
+    rule: UpdateRule,
+    v_max: VelocityLimit,
+    topology: Topology,
+    boundary: Boundary,
+    initialization: Initialization,
+    diversity: &[DiversityMeasure],
 ) -> Configuration<P>
 where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
 {
     Configuration::builder()
-        .do_(initialization::RandomSpread::new(population_size))
-        .evaluate()
+        .do_(initialization.operator(population_size))
         .update_best_individual()
-        .do_(Box::from(swarm::pso::ParticleSwarmInit::new(v_max)))
-        .do_(MinimumIndividualDistance::new())
+        .do_(v_max.swarm_init())
+        .do_(diversity_measures(diversity))
         .do_(Logger::new())
         .while_(
             conditions::LessThanN::evaluations(evaluations),
             |builder| {
                 builder
-                    .do_(Box::from(swarm::pso::ParticleVelocitiesUpdate::new(
-                        w,
-                        c1,
-                        c2,
-                        v_max,
-                    )))
-                    .do_(boundary::CosineCorrection::new())
+                    .do_(topology.velocity_update(rule, &v_max, evaluations))
+                    .do_(boundary.operator())
                     .evaluate()
                     .update_best_individual()
-                    .do_(MinimumIndividualDistance::new())
+                    .do_(diversity_measures(diversity))
                     .do_(swarm::pso::ParticleSwarmUpdate::new())
                     .do_(Logger::new())
             },
@@ -0,0 +1,203 @@
+use better_any::{Tid, TidAble};
+use mahf::{prelude::*, CustomState,
+           components::swarm::pso::{BestParticles, ParticleVelocities},
+           problems::{LimitedVectorProblem, SingleObjectiveProblem},
+           Random};
+use rand::{seq::index, Rng};
+use serde::Serialize;
+
+use super::events::ReplacedIndices;
+use super::parameters::{PersonalBestPolicy, Replacement, VelocityPolicy};
+use super::velocity::VelocityLimit;
+
+/// Selects the indices of the `n` solutions of the `population` that are replaced.
+fn replaced_indices<P: SingleObjectiveProblem>(
+    population: &[Individual<P>],
+    n: usize,
+    replacement: Replacement,
+    rng: &mut Random,
+) -> Vec<usize> {
+    match replacement {
+        Replacement::Random => index::sample(rng, population.len(), n).into_vec(),
+        Replacement::Best | Replacement::Worst => {
+            let mut indices: Vec<usize> = (0..population.len()).collect();
+            indices.sort_by(|&a, &b| {
+                population[a]
+                    .objective()
+                    .value()
+                    .total_cmp(&population[b].objective().value())
+            });
+            if replacement == Replacement::Worst {
+                indices.reverse();
+            }
+            indices.truncate(n);
+            indices
+        }
+    }
+}
+
+/// Replaces `n` solutions of the population with the solutions generated by an exploration mechanism.
+///
+/// Only the individuals are exchanged, so it can be used for algorithms without velocities or personal bests,
+/// e.g. DE. The population size stays the same.
+#[derive(Clone, Serialize)]
+pub struct ReplaceN {
+    n: u32,
+    replacement: Replacement,
+}
+
+impl ReplaceN {
+    pub fn from_params(n: u32, replacement: Replacement) -> Self {
+        Self { n, replacement }
+    }
+
+    pub fn new<P: SingleObjectiveProblem>(n: u32, replacement: Replacement) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(n, replacement))
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for ReplaceN {
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.has::<ReplacedIndices>() {
+            state.insert(ReplacedIndices::default());
+        }
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let offspring = populations.pop();
+        let population = populations.current_mut();
+        let n = (self.n as usize).min(offspring.len()).min(population.len());
+
+        let indices = replaced_indices(population, n, self.replacement, &mut state.random_mut());
+        state.borrow_mut::<ReplacedIndices>().0.extend(&indices);
+        for (i, individual) in indices.into_iter().zip(offspring) {
+            population[i] = individual;
+        }
+        Ok(())
+    }
+}
+
+/// Indices and previous personal bests of the particles replaced in the current iteration.
+#[derive(Tid)]
+pub struct ReplacedParticles<P: Problem + 'static>(Vec<(usize, Individual<P>)>);
+
+impl<P: Problem> Default for ReplacedParticles<P> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<P: Problem> CustomState<'_> for ReplacedParticles<P> {}
+
+/// Replaces `n` particles of the swarm with the solutions generated by an exploration mechanism.
+///
+/// The velocities of the replaced particles are set according to the [`VelocityPolicy`]; their personal bests
+/// are handled by [`ReplacedPersonalBests`] once the new positions are evaluated.
+#[derive(Clone, Serialize)]
+pub struct ReplaceNParticles {
+    n: u32,
+    replacement: Replacement,
+    velocity: VelocityPolicy,
+    v_max: VelocityLimit,
+}
+
+impl ReplaceNParticles {
+    pub fn from_params(n: u32, replacement: Replacement, velocity: VelocityPolicy, v_max: VelocityLimit) -> Self {
+        Self { n, replacement, velocity, v_max }
+    }
+
+    pub fn new<P>(n: u32, replacement: Replacement, velocity: VelocityPolicy, v_max: VelocityLimit) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(n, replacement, velocity, v_max))
+    }
+}
+
+impl<P> Component<P> for ReplaceNParticles
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(ReplacedParticles::<P>::default());
+        if !state.has::<ReplacedIndices>() {
+            state.insert(ReplacedIndices::default());
+        }
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let offspring = populations.pop();
+        let population = populations.current_mut();
+        let n = (self.n as usize).min(offspring.len()).min(population.len());
+
+        let mut rng = state.random_mut();
+        let indices = replaced_indices(population, n, self.replacement, &mut rng);
+        state.borrow_mut::<ReplacedIndices>().0.extend(&indices);
+
+        let mut velocities = state.borrow_mut::<ParticleVelocities<P>>();
+        let bests = state.borrow::<BestParticles<P>>();
+        let mut replaced = state.borrow_mut::<ReplacedParticles<P>>();
+
+        for (i, individual) in indices.into_iter().zip(offspring) {
+            population[i] = individual;
+            match self.velocity {
+                VelocityPolicy::Zero => velocities[i].iter_mut().for_each(|v| *v = 0.0),
+                VelocityPolicy::Random => velocities[i]
+                    .iter_mut()
+                    .zip(&self.v_max.0)
+                    .for_each(|(v, &v_max)| *v = rng.gen_range(-v_max..=v_max)),
+                VelocityPolicy::Inherit => {}
+            }
+            replaced.0.push((i, bests[i].clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Sets the personal bests of the particles replaced in this iteration according to the [`PersonalBestPolicy`].
+///
+/// Has to be executed after `ParticleSwarmUpdate`, which keeps the better of the previous personal best and the
+/// new position.
+#[derive(Clone, Serialize)]
+pub struct ReplacedPersonalBests {
+    policy: PersonalBestPolicy,
+}
+
+impl ReplacedPersonalBests {
+    pub fn from_params(policy: PersonalBestPolicy) -> Self {
+        Self { policy }
+    }
+
+    pub fn new<P>(policy: PersonalBestPolicy) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(policy))
+    }
+}
+
+impl<P> Component<P> for ReplacedPersonalBests
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(ReplacedParticles::<P>::default());
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let replaced = std::mem::take(&mut state.borrow_mut::<ReplacedParticles<P>>().0);
+        let populations = state.populations();
+        let population = populations.current();
+        let mut bests = state.borrow_mut::<BestParticles<P>>();
+
+        for (i, previous) in replaced {
+            match self.policy {
+                PersonalBestPolicy::Keep => bests[i] = previous,
+                PersonalBestPolicy::Reset => bests[i] = population[i].clone(),
+                PersonalBestPolicy::KeepBetter => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use better_any::{Tid, TidAble};
+use derive_more::{Deref, DerefMut};
+use mahf::{prelude::*, CustomState,
+           components::swarm::pso::{BestParticles, ParticleVelocities},
+           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+use rand::{seq::index, Rng};
+use serde::Serialize;
+
+use super::events::ReplacedIndices;
+use super::parameters::Initialization;
+use super::sampling::{sample, OppositionPairs};
+use super::velocity::VelocityLimit;
+
+/// Which part of the population is re-initialised by a random restart.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct RestartParams {
+    /// Number of particles that survive the restart with their velocities and personal bests: the global best and
+    /// the particles with the `keep - 1` next best personal bests (solutions, without personal bests); 0 restarts
+    /// everything.
+    pub keep: u32,
+    /// Fraction of the remaining solutions that is re-initialised; the others keep their positions.
+    pub fraction: f64,
+    /// Factor by which the population grows on each restart (IPOP); 1.0 keeps the population size.
+    pub growth: f64,
+}
+
+impl Default for RestartParams {
+    /// Restarts the whole population with the same size.
+    fn default() -> Self {
+        Self { keep: 0, fraction: 1.0, growth: 1.0 }
+    }
+}
+
+/// Number of random restarts so far.
+#[derive(Default, Clone, Serialize, Deref, DerefMut, Tid)]
+pub struct RestartCount(pub u32);
+
+impl CustomState<'_> for RestartCount {}
+
+/// Number of evaluations at the last random restart.
+#[derive(Default, Clone, Serialize, Deref, DerefMut, Tid)]
+pub struct LastRestart(pub Option<u32>);
+
+impl CustomState<'_> for LastRestart {}
+
+/// Replaces the current population with a partially re-initialised one, see [`RestartParams`].
+///
+/// The surviving particles are moved to the front of the population together with their velocities and personal
+/// bests, if any; the velocities and personal bests of the new particles are set by [`RestartedParticles`].
+///
+/// The new solutions are sampled within the domain according to the [`Initialization`] scheme and are not
+/// evaluated; opposition-based restarts have to be followed by `OppositionSelection` after the evaluation.
+#[derive(Clone, Serialize)]
+pub struct RandomRestart {
+    params: RestartParams,
+    initialization: Initialization,
+}
+
+impl RandomRestart {
+    pub fn from_params(params: RestartParams, initialization: Initialization) -> Self {
+        Self { params, initialization }
+    }
+
+    pub fn new<P>(params: RestartParams, initialization: Initialization) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(params, initialization))
+    }
+}
+
+impl<P> Component<P> for RandomRestart
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(RestartCount::default());
+        state.insert(LastRestart::default());
+        if !state.has::<OppositionPairs>() {
+            state.insert(OppositionPairs::default());
+        }
+        if !state.has::<ReplacedIndices>() {
+            state.insert(ReplacedIndices::default());
+        }
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let population = state.populations_mut().pop();
+        let size = population.len();
+        let swarm = state.has::<BestParticles<P>>();
+
+        // the particles are ranked by their personal bests, or the solutions by their objective values without them
+        let memory: Vec<Individual<P>> = if swarm {
+            state.borrow::<BestParticles<P>>().iter().cloned().collect()
+        } else {
+            population.clone()
+        };
+        let mut order: Vec<usize> = (0..size).collect();
+        order.sort_by(|&a, &b| memory[a].objective().value().total_cmp(&memory[b].objective().value()));
+
+        // the global best and the best particles survive; the global best takes the place of the last of them if
+        // no surviving particle holds it
+        let keep = (self.params.keep as usize).min(size);
+        let best = state.best_individual().map(|best| best.clone()).filter(|best| {
+            keep > 0 && !order[..keep].iter().any(|&i| memory[i].solution() == best.solution())
+        });
+        let elite = keep - best.is_some() as usize;
+        let rest = &order[keep..];
+
+        // a random fraction of the remaining particles is re-initialised
+        let restarted = (self.params.fraction * rest.len() as f64).round() as usize;
+        let mut survivors: Vec<usize> = order[..elite].to_vec();
+        if restarted < rest.len() {
+            let mut kept = vec![true; rest.len()];
+            for i in index::sample(&mut *state.random_mut(), rest.len(), restarted) {
+                kept[i] = false;
+            }
+            survivors.extend(rest.iter().zip(kept).filter(|(_, kept)| *kept).map(|(&i, _)| i));
+        }
+
+        // the surviving particles keep their velocities and personal bests
+        if swarm {
+            let mut velocities = state.borrow_mut::<ParticleVelocities<P>>();
+            let mut bests = state.borrow_mut::<BestParticles<P>>();
+            let survivor_velocities: Vec<_> = best
+                .iter()
+                .map(|best| vec![0.0; best.solution().len()])
+                .chain(survivors.iter().map(|&i| velocities[i].clone()))
+                .collect();
+            velocities.clear();
+            velocities.extend(survivor_velocities);
+            bests.clear();
+            bests.extend(best.iter().cloned().chain(survivors.iter().map(|&i| memory[i].clone())));
+        }
+        let mut individuals: Vec<Individual<P>> = best.into_iter().collect();
+        individuals.extend(survivors.iter().map(|&i| population[i].clone()));
+
+        let new_size = ((size as f64 * self.params.growth).round() as usize).max(size);
+        let restarted = new_size.saturating_sub(individuals.len());
+        let solutions = sample(self.initialization, restarted, &problem.domain(), &mut state.random_mut());
+        state.borrow_mut::<ReplacedIndices>().0.extend(individuals.len()..new_size);
+        individuals.extend(solutions.into_iter().map(Individual::new_unevaluated));
+
+        state.populations_mut().push(individuals);
+        if self.initialization == Initialization::Opposition {
+            state.borrow_mut::<OppositionPairs>().0 = restarted;
+        }
+        let evaluations = state.evaluations();
+        state.borrow_mut::<RestartCount>().0 += 1;
+        state.borrow_mut::<LastRestart>().0 = Some(evaluations);
+        Ok(())
+    }
+}
+
+/// Initialises the velocities, uniformly random within the limit, and the personal bests of the particles
+/// re-initialised by a [`RandomRestart`], i.e. those in [`ReplacedIndices`]; the surviving particles keep theirs.
+///
+/// Has to be executed after the new particles are evaluated.
+#[derive(Clone, Serialize)]
+pub struct RestartedParticles {
+    v_max: VelocityLimit,
+}
+
+impl RestartedParticles {
+    pub fn from_params(v_max: VelocityLimit) -> Self {
+        Self { v_max }
+    }
+
+    pub fn new<P>(v_max: VelocityLimit) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(v_max))
+    }
+}
+
+impl<P> Component<P> for RestartedParticles
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut replaced = state.borrow::<ReplacedIndices>().0.clone();
+        replaced.sort_unstable();
+        let populations = state.populations();
+        let population = populations.current();
+        let mut rng = state.random_mut();
+        let mut velocities = state.borrow_mut::<ParticleVelocities<P>>();
+        let mut bests = state.borrow_mut::<BestParticles<P>>();
+
+        for i in replaced {
+            let velocity: Vec<f64> = self.v_max.0.iter().map(|&v_max| rng.gen_range(-v_max..=v_max)).collect();
+            if i < velocities.len() {
+                velocities[i] = velocity;
+                bests[i] = population[i].clone();
+            } else {
+                velocities.push(velocity);
+                bests.push(population[i].clone());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use std::ops::Range;
+
+use better_any::{Tid, TidAble};
+use mahf::{prelude::*, CustomState,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem},
+           Random};
+use rand::{seq::SliceRandom, Rng};
+use serde::Serialize;
+
+use super::parameters::Initialization;
+
+/// Number of opposite pairs at the end of the current population that still have to be reduced to the better
+/// solution of each pair, see [`OppositionSelection`].
+#[derive(Default, Tid)]
+pub struct OppositionPairs(pub(crate) usize);
+
+impl CustomState<'_> for OppositionPairs {}
+
+/// Radical inverse of `index` in the given `base`, i.e. the Halton sequence of the base.
+fn radical_inverse(mut index: u64, base: u64) -> f64 {
+    let mut inverse = 0.0;
+    let mut fraction = 1.0 / base as f64;
+    while index > 0 {
+        inverse += (index % base) as f64 * fraction;
+        index /= base;
+        fraction /= base as f64;
+    }
+    inverse
+}
+
+/// The first `n` prime numbers, used as bases of the Halton sequence.
+fn primes(n: usize) -> Vec<u64> {
+    let mut primes: Vec<u64> = Vec::with_capacity(n);
+    let mut candidate = 2;
+    while primes.len() < n {
+        if primes.iter().all(|p| candidate % p != 0) {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+/// Samples `n` points of the unit hypercube of dimension `d`.
+fn unit_sample(initialization: Initialization, n: usize, d: usize, rng: &mut Random) -> Vec<Vec<f64>> {
+    match initialization {
+        Initialization::Random | Initialization::Opposition => {
+            (0..n).map(|_| (0..d).map(|_| rng.gen::<f64>()).collect()).collect()
+        }
+        Initialization::Lhs => {
+            // one point in each of the n strata of every dimension, combined by random permutations
+            let mut points = vec![vec![0.0; d]; n];
+            for j in 0..d {
+                let mut strata: Vec<usize> = (0..n).collect();
+                strata.shuffle(rng);
+                for (point, stratum) in points.iter_mut().zip(strata) {
+                    point[j] = (stratum as f64 + rng.gen::<f64>()) / n as f64;
+                }
+            }
+            points
+        }
+        Initialization::Halton => {
+            // randomised by a random shift (Cranley-Patterson rotation), skipping the origin
+            let bases = primes(d);
+            let shift: Vec<f64> = (0..d).map(|_| rng.gen()).collect();
+            (1..=n as u64)
+                .map(|i| {
+                    bases
+                        .iter()
+                        .zip(&shift)
+                        .map(|(&base, s)| (radical_inverse(i, base) + s).fract())
+                        .collect()
+                })
+                .collect()
+        }
+        Initialization::Sobol => {
+            // Owen-scrambled Sobol sequence, seeded from the random generator of the run
+            assert!(d <= sobol_burley::NUM_DIMENSIONS as usize, "Sobol sampling supports at most 256 dimensions");
+            let seed: u32 = rng.gen();
+            (0..n as u32)
+                .map(|i| (0..d as u32).map(|j| sobol_burley::sample(i, j, seed) as f64).collect())
+                .collect()
+        }
+    }
+}
+
+/// Samples `n` solutions within the `domain` according to the `initialization` scheme.
+///
+/// Opposition-based initialisation returns `2n` solutions: random solutions each followed by its opposite
+/// solution, of which [`OppositionSelection`] keeps the better one after evaluation.
+pub(crate) fn sample(
+    initialization: Initialization,
+    n: usize,
+    domain: &[Range<f64>],
+    rng: &mut Random,
+) -> Vec<Vec<f64>> {
+    let scale = |point: Vec<f64>| -> Vec<f64> {
+        point
+            .iter()
+            .zip(domain)
+            .map(|(u, range)| range.start + u * (range.end - range.start))
+            .collect()
+    };
+    let solutions = unit_sample(initialization, n, domain.len(), rng).into_iter().map(scale);
+
+    match initialization {
+        Initialization::Opposition => solutions
+            .flat_map(|solution| {
+                let opposite = solution
+                    .iter()
+                    .zip(domain)
+                    .map(|(x, range)| range.start + range.end - x)
+                    .collect();
+                [solution, opposite]
+            })
+            .collect(),
+        _ => solutions.collect(),
+    }
+}
+
+/// Creates the initial population of `population_size` solutions with the `initialization` scheme.
+///
+/// The solutions are not evaluated; with opposition-based initialisation, the population contains both solutions
+/// of each opposite pair.
+#[derive(Clone, Serialize)]
+pub struct SampledInit {
+    initialization: Initialization,
+    population_size: u32,
+}
+
+impl SampledInit {
+    pub fn from_params(initialization: Initialization, population_size: u32) -> Self {
+        Self { initialization, population_size }
+    }
+
+    pub fn new<P>(initialization: Initialization, population_size: u32) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(initialization, population_size))
+    }
+}
+
+impl<P> Component<P> for SampledInit
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.has::<OppositionPairs>() {
+            state.insert(OppositionPairs::default());
+        }
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let n = self.population_size as usize;
+        let population = sample(self.initialization, n, &problem.domain(), &mut state.random_mut())
+            .into_iter()
+            .map(Individual::new_unevaluated)
+            .collect();
+        state.populations_mut().push(population);
+        if self.initialization == Initialization::Opposition {
+            state.borrow_mut::<OppositionPairs>().0 = n;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps the better solution of each evaluated opposite pair at the end of the current population.
+#[derive(Clone, Serialize)]
+pub struct OppositionSelection;
+
+impl OppositionSelection {
+    pub fn from_params() -> Self {
+        Self
+    }
+
+    pub fn new<P: SingleObjectiveProblem>() -> Box<dyn Component<P>> {
+        Box::new(Self::from_params())
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for OppositionSelection {
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.has::<OppositionPairs>() {
+            state.insert(OppositionPairs::default());
+        }
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let pairs = std::mem::take(&mut state.borrow_mut::<OppositionPairs>().0);
+        let mut populations = state.populations_mut();
+        let population = populations.current_mut();
+
+        let opposites = population.split_off(population.len() - 2 * pairs);
+        let mut opposites = opposites.into_iter();
+        while let (Some(solution), Some(opposite)) = (opposites.next(), opposites.next()) {
+            if opposite.objective().value() < solution.objective().value() {
+                population.push(opposite);
+            } else {
+                population.push(solution);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOMAIN: [Range<f64>; 3] = [-5.0..5.0, 0.0..1.0, 10.0..30.0];
+
+    fn within_domain(solution: &[f64]) -> bool {
+        solution.len() == DOMAIN.len() && solution.iter().zip(&DOMAIN).all(|(x, range)| range.contains(x))
+    }
+
+    #[test]
+    fn samples_within_the_domain() {
+        for initialization in [Initialization::Random, Initialization::Lhs, Initialization::Sobol, Initialization::Halton] {
+            let solutions = sample(initialization, 20, &DOMAIN, &mut Random::new(0));
+            assert_eq!(solutions.len(), 20);
+            assert!(solutions.iter().all(|solution| within_domain(solution)), "{} left the domain", initialization);
+        }
+    }
+
+    #[test]
+    fn latin_hypercube_covers_every_stratum() {
+        let n = 10;
+        let solutions = sample(Initialization::Lhs, n, &DOMAIN, &mut Random::new(0));
+        for (d, range) in DOMAIN.iter().enumerate() {
+            let mut strata: Vec<usize> = solutions
+                .iter()
+                .map(|solution| ((solution[d] - range.start) / (range.end - range.start) * n as f64) as usize)
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..n).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn opposition_appends_the_opposite_of_each_solution() {
+        let solutions = sample(Initialization::Opposition, 5, &DOMAIN, &mut Random::new(0));
+        assert_eq!(solutions.len(), 10);
+        for pair in solutions.chunks(2) {
+            assert!(within_domain(&pair[0]) && within_domain(&pair[1]));
+            for ((x, opposite), range) in pair[0].iter().zip(&pair[1]).zip(&DOMAIN) {
+                assert!((x + opposite - range.start - range.end).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn halton_uses_the_first_primes() {
+        assert_eq!(primes(5), [2, 3, 5, 7, 11]);
+        assert_eq!(radical_inverse(1, 2), 0.5);
+        assert_eq!(radical_inverse(6, 2), 0.375);
+        assert!((radical_inverse(5, 3) - 7.0 / 9.0).abs() < 1e-12);
+    }
+}
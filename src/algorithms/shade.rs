@@ -1,10 +1,41 @@
 use mahf::{prelude::*,
-           components::{initialization,
-                        measures::{diversity::{MinimumIndividualDistance}, }},
            configuration::Configuration, logging::Logger,
-           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+           problems::{LimitedVectorProblem, SingleObjectiveProblem, KnownOptimumProblem}};
 use eyre::WrapErr;
 
+use super::adaptation::NewPopAdaptation;
+use super::diversity::diversity_measures;
+use super::events::AttributeSuccess;
+use super::parameters::{Boundary, DiversityMeasure, Initialization};
+use super::exploration_pso::{BaseAlgorithm, ExplorationMechanism, NewPopAdaptationParams};
+
+/// One SHADE generation: adaptation of F and CR, current-to-pbest/1 mutation with archive, crossover, evaluation,
+/// and the update of the archive, the history and the population.
+pub(crate) fn generation<P>(
+    population_size: u32,
+    y: u32,
+    p_min: f64,
+    max_archive: usize,
+    f: f64,
+    crossover: Box<dyn Component<P>>,
+    boundary: Boundary,
+) -> Box<dyn Component<P>>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    Configuration::builder()
+        .do_(Box::from(mapping::de::SHADEAdaptation::new().expect("failed to construct SHADE Adaptation")))
+        .do_(selection::de::SHADECurrentToPBest::new(y, p_min, population_size as usize, max_archive).wrap_err("failed to construct DE selection").unwrap())
+        .do_(mutation::de::DEMutation::new(y, f).wrap_err("failed to construct DE mutation").unwrap())
+        .do_(crossover)
+        .do_(boundary.operator())
+        .evaluate()
+        .update_best_individual()
+        .do_(components::archive::DEKeepParentsArchiveUpdate::new(max_archive))
+        .do_(mapping::de::SHADEAdaptationHistoryUpdate::new().expect("failed to construct SHADE AdaptationHistory"))
+        .do_(replacement::KeepBetterAtIndex::new())
+        .build_component()
+}
+
 pub fn shade<P>(
     evaluations: u32,
     population_size: u32,
@@ -14,33 +45,76 @@ pub fn shade<P>(
     history: usize,
     f: f64,
     crossover: Box<dyn Component<P>>,
+    boundary: Boundary,
+    initialization: Initialization,
+    diversity: &[DiversityMeasure],
 ) -> Configuration<P>
 where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
 {
     Configuration::builder()
-        .do_(initialization::RandomSpread::new(population_size))
-        .evaluate()
+        .do_(initialization.operator(population_size))
+        .update_best_individual()
+        .do_(diversity_measures(diversity))
+        .do_(mapping::de::SHADEAdaptationInit::new(history).expect("failed to initialise SHADE adaptation states"))
+        .do_(Logger::new())
+        .while_(
+            conditions::LessThanN::evaluations(evaluations),
+            |builder|{
+                builder
+                    .do_(generation(population_size, y, p_min, max_archive, f, crossover, boundary))
+                    .do_(diversity_measures(diversity))
+                    .do_(Logger::new())
+            }
+        )
+        .build()
+}
+
+/// SHADE that executes the exploration `mechanism` instead of a generation whenever the `condition` is met.
+///
+/// The mechanism replaces individuals without touching the SHADE state: the history of F and CR is only updated
+/// from the successful parameters of regular generations, and the archive keeps the parents discarded by them.
+pub fn exploration_shade<P>(
+    evaluations: u32,
+    population_size: u32,
+    y: u32,
+    p_min: f64,
+    max_archive: usize,
+    history: usize,
+    f: f64,
+    crossover: Box<dyn Component<P>>,
+    boundary: Boundary,
+    initialization: Initialization,
+    diversity: &[DiversityMeasure],
+    condition: Box<dyn Condition<P>>,
+    mechanism: ExplorationMechanism,
+    adaptation: Option<NewPopAdaptationParams>,
+    attribution_window: u32,
+) -> Configuration<P>
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64> + KnownOptimumProblem,
+{
+    let adaptation = adaptation.filter(|_| mechanism.replaces_new_pop());
+    let exploration = mechanism.component(&BaseAlgorithm::De, adaptation, boundary, initialization);
+
+    Configuration::builder()
+        .do_(initialization.operator(population_size))
         .update_best_individual()
-        .do_(MinimumIndividualDistance::new())
+        .do_(diversity_measures(diversity))
         .do_(mapping::de::SHADEAdaptationInit::new(history).expect("failed to initialise SHADE adaptation states"))
         .do_(Logger::new())
         .while_(
             conditions::LessThanN::evaluations(evaluations),
             |builder|{
                 builder
-                    .do_(Box::from(mapping::de::SHADEAdaptation::new().expect("failed to construct SHADE Adaptation")))
-                    .do_(selection::de::SHADECurrentToPBest::new(y, p_min, population_size as usize, max_archive).wrap_err("failed to construct DE selection").unwrap())
-                    .do_(mutation::de::DEMutation::new(y, f).wrap_err("failed to construct DE mutation").unwrap())
-                    .do_(crossover)
-                    .do_(boundary::CosineCorrection::new())
-                    .evaluate()
-                    .update_best_individual()
-                    .do_(components::archive::DEKeepParentsArchiveUpdate::new(max_archive))
-                    .do_(mapping::de::SHADEAdaptationHistoryUpdate::new().expect("failed to construct SHADE AdaptationHistory"))
-                    .do_(replacement::KeepBetterAtIndex::new())
-                    .do_(MinimumIndividualDistance::new())
+                    .if_else_(condition, |builder| builder.do_(exploration), |builder| {
+                        builder.do_(generation(population_size, y, p_min, max_archive, f, crossover, boundary))
+                    })
+                    .do_(AttributeSuccess::new(attribution_window))
+                    .do_if_some_(adaptation.map(|adaptation| {
+                        NewPopAdaptation::new(adaptation.window, adaptation.step, 1, population_size)
+                    }))
+                    .do_(diversity_measures(diversity))
                     .do_(Logger::new())
             }
         )
         .build()
-}
\ No newline at end of file
+}
@@ -0,0 +1,76 @@
+use better_any::{Tid, TidAble};
+use mahf::{CustomState, SingleObjective, Random};
+use rand::{seq::index, Rng};
+
+use super::parameters::Topology;
+
+/// Number of particles each particle informs in the random topology.
+const RANDOM_INFORMANTS: usize = 3;
+/// Number of neighbours on each side in the ring the small-world topology starts from.
+const SMALL_WORLD_NEIGHBOURS: usize = 2;
+/// Probability of rewiring a link of the ring in the small-world topology.
+const SMALL_WORLD_REWIRING: f64 = 0.1;
+
+/// Neighbourhood of each particle, including the particle itself, and the best objective value when it was built.
+#[derive(Default, Tid)]
+pub struct Neighbourhoods {
+    pub(crate) neighbours: Vec<Vec<usize>>,
+    pub(crate) last_best: Option<SingleObjective>,
+}
+
+impl CustomState<'_> for Neighbourhoods {}
+
+/// Adds `informant` to the neighbourhood of `particle`.
+fn inform(neighbours: &mut [Vec<usize>], informant: usize, particle: usize) {
+    if !neighbours[particle].contains(&informant) {
+        neighbours[particle].push(informant);
+    }
+}
+
+/// Adds `a` and `b` to each other's neighbourhood.
+fn link(neighbours: &mut [Vec<usize>], a: usize, b: usize) {
+    inform(neighbours, a, b);
+    inform(neighbours, b, a);
+}
+
+impl Topology {
+    /// Builds the neighbourhoods of a swarm of size `n`.
+    pub(crate) fn neighbourhoods(self, n: usize, rng: &mut Random) -> Vec<Vec<usize>> {
+        let mut neighbours: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+
+        match self {
+            Topology::Global => (0..n).for_each(|i| (0..n).for_each(|j| link(&mut neighbours, i, j))),
+            Topology::Ring => (0..n).for_each(|i| link(&mut neighbours, i, (i + 1) % n)),
+            Topology::VonNeumann => {
+                // particles on a wrapped grid with as many columns as rows
+                let columns = (n as f64).sqrt().ceil() as usize;
+                (0..n).for_each(|i| {
+                    link(&mut neighbours, i, (i + 1) % n);
+                    link(&mut neighbours, i, (i + columns) % n);
+                });
+            }
+            Topology::Random => {
+                // each particle informs itself and a few random others
+                for i in 0..n {
+                    for j in index::sample(rng, n, RANDOM_INFORMANTS.min(n)) {
+                        inform(&mut neighbours, i, j);
+                    }
+                }
+            }
+            Topology::SmallWorld => {
+                // Watts-Strogatz: a ring whose links are rewired with a small probability
+                for i in 0..n {
+                    for k in 1..=SMALL_WORLD_NEIGHBOURS {
+                        let j = if rng.gen_bool(SMALL_WORLD_REWIRING) {
+                            rng.gen_range(0..n)
+                        } else {
+                            (i + k) % n
+                        };
+                        link(&mut neighbours, i, j);
+                    }
+                }
+            }
+        }
+        neighbours
+    }
+}
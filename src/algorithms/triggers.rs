@@ -0,0 +1,405 @@
+use std::{fmt, str::FromStr};
+
+use mahf::{prelude::*,
+           components::measures::diversity::{MinimumIndividualDistance, NormalizedDiversityLens},
+           conditions::common::PartialEqChecker,
+           lens::common::BestObjectiveValueLens,
+           prelude::common::Evaluations,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem},
+           state::StateReq, CustomState};
+use better_any::{Tid, TidAble};
+use clap::ValueEnum;
+use serde::{Serialize, Serializer};
+
+use super::diversity::DiversityBelow;
+use super::parameters::{CooldownUnit, DiversityMeasure};
+
+/// Condition that triggers the exploration mechanism, possibly composed of several signals.
+///
+/// Triggers are written as function calls, e.g. `or(stagnation(0.05),diversity(0.1))`:
+/// - `stagnation(x)`: the best objective value stagnated for the fraction `x` of the evaluation budget,
+/// - `diversity(x)`: the normalized minimum individual distance is below `x`,
+/// - `diversity(x,measure)`: the normalized diversity with the [`DiversityMeasure`] is below `x`, e.g. `radius`,
+/// - `and(..)`, `or(..)` and `not(..)` combine other triggers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Trigger {
+    Stagnation(f64),
+    Diversity(f64, DiversityMeasure),
+    And(Vec<Trigger>),
+    Or(Vec<Trigger>),
+    Not(Box<Trigger>),
+}
+
+impl Trigger {
+    pub fn condition<P>(&self, evaluations: u32) -> Box<dyn Condition<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        match self {
+            Trigger::Stagnation(fraction) => {
+                let eval_interval = fraction * evaluations as f64;
+                conditions::StagnationForN::new(
+                    eval_interval as usize,
+                    ValueOf::<Evaluations>::new(),
+                    BestObjectiveValueLens::new(),
+                    PartialEqChecker::new(),
+                )
+            }
+            Trigger::Diversity(threshold, DiversityMeasure::MinimumDistance) => conditions::LessThanN::new(
+                *threshold,
+                NormalizedDiversityLens::<MinimumIndividualDistance>::new(),
+            ),
+            Trigger::Diversity(threshold, measure) => DiversityBelow::new(*measure, *threshold),
+            Trigger::And(triggers) => AllOf::new(triggers.iter().map(|t| t.condition(evaluations)).collect()),
+            Trigger::Or(triggers) => AnyOf::new(triggers.iter().map(|t| t.condition(evaluations)).collect()),
+            Trigger::Not(trigger) => Not::new(trigger.condition(evaluations)),
+        }
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, triggers) = match self {
+            Trigger::Stagnation(fraction) => return write!(f, "stagnation({})", fraction),
+            Trigger::Diversity(threshold, DiversityMeasure::MinimumDistance) => {
+                return write!(f, "diversity({})", threshold)
+            }
+            Trigger::Diversity(threshold, measure) => return write!(f, "diversity({},{})", threshold, measure),
+            Trigger::Not(trigger) => return write!(f, "not({})", trigger),
+            Trigger::And(triggers) => ("and", triggers),
+            Trigger::Or(triggers) => ("or", triggers),
+        };
+        write!(f, "{}(", name)?;
+        for (i, trigger) in triggers.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", trigger)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Serialize for Trigger {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Splits the arguments of a trigger at the commas that are not nested in parentheses.
+fn split_arguments(arguments: &str) -> Result<Vec<&str>, String> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1).ok_or("unbalanced parentheses")?,
+            ',' if depth == 0 => {
+                parts.push(&arguments[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err("unbalanced parentheses".to_string());
+    }
+    parts.push(&arguments[start..]);
+    Ok(parts)
+}
+
+impl FromStr for Trigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, arguments) = s
+            .strip_suffix(')')
+            .and_then(|s| s.split_once('('))
+            .ok_or_else(|| format!("expected a trigger like `stagnation(0.05)`, found `{}`", s))?;
+        let arguments = split_arguments(arguments)?;
+
+        let value = || -> Result<f64, String> {
+            match arguments.as_slice() {
+                [value] => value.trim().parse().map_err(|_| format!("invalid value in `{}`", s)),
+                _ => Err(format!("`{}` takes exactly one value", name)),
+            }
+        };
+        let triggers = || -> Result<Vec<Trigger>, String> {
+            arguments.iter().map(|argument| argument.parse()).collect()
+        };
+
+        match name.trim() {
+            "stagnation" => Ok(Trigger::Stagnation(value()?)),
+            "diversity" => match arguments.as_slice() {
+                [_] => Ok(Trigger::Diversity(value()?, DiversityMeasure::MinimumDistance)),
+                [threshold, measure] => Ok(Trigger::Diversity(
+                    threshold.trim().parse().map_err(|_| format!("invalid value in `{}`", s))?,
+                    <DiversityMeasure as ValueEnum>::from_str(measure.trim(), false)?,
+                )),
+                _ => Err("`diversity` takes a value and optionally a measure".to_string()),
+            },
+            "and" => Ok(Trigger::And(triggers()?)),
+            "or" => Ok(Trigger::Or(triggers()?)),
+            "not" => match triggers()?.as_slice() {
+                [trigger] => Ok(Trigger::Not(Box::new(trigger.clone()))),
+                _ => Err("`not` takes exactly one trigger".to_string()),
+            },
+            other => Err(format!(
+                "unknown trigger `{}`; expected stagnation, diversity, and, or or not",
+                other
+            )),
+        }
+    }
+}
+
+/// Forwards `init` and `require` to all `conditions`.
+fn init_all<P: Problem>(conditions: &[Box<dyn Condition<P>>], problem: &P, state: &mut State<P>) -> ExecResult<()> {
+    for condition in conditions {
+        condition.init(problem, state)?;
+    }
+    Ok(())
+}
+
+fn require_all<P: Problem>(conditions: &[Box<dyn Condition<P>>], problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+    for condition in conditions {
+        condition.require(problem, state_req)?;
+    }
+    Ok(())
+}
+
+/// True if all conditions are true.
+///
+/// Unlike `&&`, every condition is evaluated in every iteration, so that stateful conditions
+/// like `StagnationForN` keep track of the state.
+#[derive(Clone, Serialize)]
+#[serde(bound = "")]
+pub struct AllOf<P: Problem> {
+    conditions: Vec<Box<dyn Condition<P>>>,
+}
+
+impl<P: Problem> AllOf<P> {
+    pub fn from_params(conditions: Vec<Box<dyn Condition<P>>>) -> Self {
+        Self { conditions }
+    }
+
+    pub fn new(conditions: Vec<Box<dyn Condition<P>>>) -> Box<dyn Condition<P>> {
+        Box::new(Self::from_params(conditions))
+    }
+}
+
+impl<P: Problem> Condition<P> for AllOf<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        init_all(&self.conditions, problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        require_all(&self.conditions, problem, state_req)
+    }
+
+    fn evaluate(&self, problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let mut all = true;
+        for condition in &self.conditions {
+            all &= condition.evaluate(problem, state)?;
+        }
+        Ok(all)
+    }
+}
+
+/// True if any condition is true.
+///
+/// Unlike `||`, every condition is evaluated in every iteration, see [`AllOf`].
+#[derive(Clone, Serialize)]
+#[serde(bound = "")]
+pub struct AnyOf<P: Problem> {
+    conditions: Vec<Box<dyn Condition<P>>>,
+}
+
+impl<P: Problem> AnyOf<P> {
+    pub fn from_params(conditions: Vec<Box<dyn Condition<P>>>) -> Self {
+        Self { conditions }
+    }
+
+    pub fn new(conditions: Vec<Box<dyn Condition<P>>>) -> Box<dyn Condition<P>> {
+        Box::new(Self::from_params(conditions))
+    }
+}
+
+impl<P: Problem> Condition<P> for AnyOf<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        init_all(&self.conditions, problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        require_all(&self.conditions, problem, state_req)
+    }
+
+    fn evaluate(&self, problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let mut any = false;
+        for condition in &self.conditions {
+            any |= condition.evaluate(problem, state)?;
+        }
+        Ok(any)
+    }
+}
+
+/// True if the condition is false.
+#[derive(Clone, Serialize)]
+#[serde(bound = "")]
+pub struct Not<P: Problem> {
+    condition: Box<dyn Condition<P>>,
+}
+
+impl<P: Problem> Not<P> {
+    pub fn from_params(condition: Box<dyn Condition<P>>) -> Self {
+        Self { condition }
+    }
+
+    pub fn new(condition: Box<dyn Condition<P>>) -> Box<dyn Condition<P>> {
+        Box::new(Self::from_params(condition))
+    }
+}
+
+impl<P: Problem> Condition<P> for Not<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        self.condition.init(problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        self.condition.require(problem, state_req)
+    }
+
+    fn evaluate(&self, problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        Ok(!self.condition.evaluate(problem, state)?)
+    }
+}
+
+/// Iteration or evaluation at which the exploration mechanism was triggered last.
+#[derive(Default, Tid)]
+pub struct LastTriggered(Option<u32>);
+
+impl CustomState<'_> for LastTriggered {}
+
+/// True if the condition is true and at least `gap` iterations or evaluations passed since it was last true.
+///
+/// The condition is evaluated in every iteration, also during the cooldown.
+#[derive(Clone, Serialize)]
+#[serde(bound = "")]
+pub struct Cooldown<P: Problem> {
+    condition: Box<dyn Condition<P>>,
+    gap: u32,
+    unit: CooldownUnit,
+}
+
+impl<P: Problem> Cooldown<P> {
+    pub fn from_params(condition: Box<dyn Condition<P>>, gap: u32, unit: CooldownUnit) -> Self {
+        Self { condition, gap, unit }
+    }
+
+    pub fn new(condition: Box<dyn Condition<P>>, gap: u32, unit: CooldownUnit) -> Box<dyn Condition<P>> {
+        Box::new(Self::from_params(condition, gap, unit))
+    }
+
+    fn now(&self, state: &State<P>) -> u32 {
+        match self.unit {
+            CooldownUnit::Iterations => state.iterations(),
+            CooldownUnit::Evaluations => state.evaluations(),
+        }
+    }
+}
+
+impl<P: Problem> Condition<P> for Cooldown<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(LastTriggered::default());
+        self.condition.init(problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        self.condition.require(problem, state_req)
+    }
+
+    fn evaluate(&self, problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let triggered = self.condition.evaluate(problem, state)?;
+        let now = self.now(state);
+        let cooling_down = state
+            .borrow::<LastTriggered>()
+            .0
+            .is_some_and(|last| now < last + self.gap);
+
+        if triggered && !cooling_down {
+            state.borrow_mut::<LastTriggered>().0 = Some(now);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_arguments_at_top_level_commas() {
+        assert_eq!(split_arguments("0.1").unwrap(), ["0.1"]);
+        assert_eq!(
+            split_arguments("stagnation(0.05),and(diversity(0.1),not(stagnation(0.2)))").unwrap(),
+            ["stagnation(0.05)", "and(diversity(0.1),not(stagnation(0.2)))"]
+        );
+        assert!(split_arguments("stagnation(0.05").is_err());
+        assert!(split_arguments("stagnation0.05)").is_err());
+    }
+
+    #[test]
+    fn parses_nested_triggers() {
+        let trigger: Trigger = "or(stagnation(0.05), and(diversity(0.1,radius),not(stagnation(0.2))))".parse().unwrap();
+        assert_eq!(
+            trigger,
+            Trigger::Or(vec![
+                Trigger::Stagnation(0.05),
+                Trigger::And(vec![
+                    Trigger::Diversity(0.1, DiversityMeasure::Radius),
+                    Trigger::Not(Box::new(Trigger::Stagnation(0.2))),
+                ]),
+            ])
+        );
+        assert_eq!(
+            "diversity(0.1)".parse::<Trigger>().unwrap(),
+            Trigger::Diversity(0.1, DiversityMeasure::MinimumDistance)
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in [
+            "stagnation(0.05)",
+            "diversity(0.1)",
+            "diversity(0.1,true_diversity)",
+            "not(diversity(0.3,pairwise))",
+            "or(stagnation(0.05),and(diversity(0.1,radius),not(stagnation(0.2))))",
+        ] {
+            let trigger: Trigger = s.parse().unwrap();
+            assert_eq!(trigger.to_string(), s);
+            assert_eq!(trigger.to_string().parse::<Trigger>().unwrap(), trigger);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_triggers() {
+        for s in [
+            "",
+            "stagnation",
+            "stagnation()",
+            "stagnation(x)",
+            "stagnation(0.1,0.2)",
+            "diversity(0.1,unknown)",
+            "diversity(0.1,radius,pairwise)",
+            "not(stagnation(0.1),diversity(0.1))",
+            "or(stagnation(0.1),diversity(0.1)",
+            "or(stagnation(0.1)),diversity(0.1))",
+            "xor(stagnation(0.1),diversity(0.1))",
+        ] {
+            assert!(s.parse::<Trigger>().is_err(), "`{}` should be rejected", s);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::ops::Range;
+
+use mahf::{prelude::*,
+           components::swarm::pso::{BestParticles, ParticleVelocities},
+           configuration::Configuration,
+           problems::{LimitedVectorProblem, SingleObjectiveProblem}};
+use rand::Rng;
+use serde::Serialize;
+
+use super::parameters::Topology;
+use super::topology::Neighbourhoods;
+
+/// Rule determining the inertia weight and acceleration coefficients of PSO over the run.
+///
+/// Time-varying rules interpolate between their start and end values depending on the fraction of the evaluation
+/// budget used so far.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum UpdateRule {
+    /// Constant `w`, `c1` and `c2`.
+    Constant { w: f64, c1: f64, c2: f64 },
+    /// Inertia weight decreasing linearly from `w_start` to `w_end`.
+    LinearInertia { w_start: f64, w_end: f64, c1: f64, c2: f64 },
+    /// Inertia weight decreasing from `w_start` to `w_end` with `(1 - t)^exponent`.
+    NonlinearInertia { w_start: f64, w_end: f64, exponent: f64, c1: f64, c2: f64 },
+    /// Time-varying acceleration coefficients (Ratnaweera et al. 2004) with linearly decreasing inertia weight.
+    Tvac { w_start: f64, w_end: f64, c1_start: f64, c1_end: f64, c2_start: f64, c2_end: f64 },
+    /// Constriction coefficient of Clerc and Kennedy 2002 with `phi1 + phi2 > 4`.
+    Constriction { phi1: f64, phi2: f64 },
+}
+
+/// Maximum absolute velocity of PSO in each dimension.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct VelocityLimit(pub Vec<f64>);
+
+impl VelocityLimit {
+    /// Limits the velocity to the `fraction` of the width of the `domain` in each dimension.
+    pub fn from_domain(fraction: f64, domain: &[Range<f64>]) -> Self {
+        Self(domain.iter().map(|range| fraction * (range.end - range.start)).collect())
+    }
+
+    /// The limit, if it is the same in all dimensions.
+    pub fn uniform(&self) -> Option<f64> {
+        let first = *self.0.first()?;
+        self.0.iter().all(|&v_max| v_max == first).then_some(first)
+    }
+
+    /// Largest limit over all dimensions.
+    pub fn max(&self) -> f64 {
+        self.0.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Initialises the velocities, uniformly random within the limit, and the personal bests of the swarm.
+    ///
+    /// Uses the initialisation of MAHF if the limit is the same in all dimensions.
+    pub fn swarm_init<P>(&self) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        let init: Box<dyn Component<P>> = Box::from(swarm::pso::ParticleSwarmInit::new(self.max()));
+        match self.uniform() {
+            Some(_) => init,
+            None => Configuration::builder()
+                .do_(init)
+                .do_(VelocitiesInit::new(self.clone()))
+                .build_component(),
+        }
+    }
+}
+
+/// Redraws the velocities of the swarm uniformly within the per-dimension [`VelocityLimit`].
+#[derive(Clone, Serialize)]
+pub struct VelocitiesInit {
+    v_max: VelocityLimit,
+}
+
+impl VelocitiesInit {
+    pub fn from_params(v_max: VelocityLimit) -> Self {
+        Self { v_max }
+    }
+
+    pub fn new<P>(v_max: VelocityLimit) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(v_max))
+    }
+}
+
+impl<P> Component<P> for VelocitiesInit
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let n = state.populations().current().len();
+        let mut rng = state.random_mut();
+        let mut velocities = state.borrow_mut::<ParticleVelocities<P>>();
+
+        for i in 0..n {
+            velocities[i]
+                .iter_mut()
+                .zip(&self.v_max.0)
+                .for_each(|(v, &v_max)| *v = rng.gen_range(-v_max..=v_max));
+        }
+        Ok(())
+    }
+}
+
+/// Linear interpolation between `start` and `end`.
+fn interpolate(start: f64, end: f64, t: f64) -> f64 {
+    start + (end - start) * t
+}
+
+impl UpdateRule {
+    /// Inertia weight and acceleration coefficients `(w, c1, c2)` after the fraction `t` of the budget.
+    pub fn coefficients(&self, t: f64) -> (f64, f64, f64) {
+        match *self {
+            UpdateRule::Constant { w, c1, c2 } => (w, c1, c2),
+            UpdateRule::LinearInertia { w_start, w_end, c1, c2 } => (interpolate(w_start, w_end, t), c1, c2),
+            UpdateRule::NonlinearInertia { w_start, w_end, exponent, c1, c2 } => {
+                (w_end + (w_start - w_end) * (1.0 - t).powf(exponent), c1, c2)
+            }
+            UpdateRule::Tvac { w_start, w_end, c1_start, c1_end, c2_start, c2_end } => (
+                interpolate(w_start, w_end, t),
+                interpolate(c1_start, c1_end, t),
+                interpolate(c2_start, c2_end, t),
+            ),
+            UpdateRule::Constriction { phi1, phi2 } => {
+                // v = chi * (v + phi1 r1 (p - x) + phi2 r2 (g - x))
+                let phi = phi1 + phi2;
+                let chi = 2.0 / (2.0 - phi - (phi * phi - 4.0 * phi).sqrt()).abs();
+                (chi, chi * phi1, chi * phi2)
+            }
+        }
+    }
+}
+
+/// Updates the velocities and positions of the particles towards their personal best and the best personal best
+/// in their neighbourhood, given by the [`Topology`], with the coefficients of the [`UpdateRule`].
+///
+/// Velocities are limited per dimension by the [`VelocityLimit`].
+///
+/// The neighbourhoods are built once, except for the random topology, which is rebuilt after each iteration
+/// without improvement of the global best. All neighbourhoods are rebuilt when the swarm size changes.
+#[derive(Clone, Serialize)]
+pub struct NeighbourhoodVelocitiesUpdate {
+    rule: UpdateRule,
+    v_max: VelocityLimit,
+    topology: Topology,
+    /// Evaluation budget the progress of time-varying rules refers to.
+    evaluations: u32,
+}
+
+impl NeighbourhoodVelocitiesUpdate {
+    pub fn from_params(rule: UpdateRule, v_max: VelocityLimit, topology: Topology, evaluations: u32) -> Self {
+        Self { rule, v_max, topology, evaluations }
+    }
+
+    pub fn new<P>(rule: UpdateRule, v_max: VelocityLimit, topology: Topology, evaluations: u32) -> Box<dyn Component<P>>
+    where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(rule, v_max, topology, evaluations))
+    }
+}
+
+impl<P> Component<P> for NeighbourhoodVelocitiesUpdate
+where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(Neighbourhoods::default());
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let progress = (state.evaluations() as f64 / self.evaluations as f64).min(1.0);
+        let (w, c1, c2) = self.rule.coefficients(progress);
+
+        let best = state.best_objective_value();
+        let mut populations = state.populations_mut();
+        let population = populations.current_mut();
+        let mut rng = state.random_mut();
+        let mut neighbourhoods = state.borrow_mut::<Neighbourhoods>();
+
+        let improved = match (best, neighbourhoods.last_best) {
+            (Some(best), Some(last_best)) => best < last_best,
+            _ => true,
+        };
+        if neighbourhoods.neighbours.len() != population.len() || (self.topology == Topology::Random && !improved) {
+            neighbourhoods.neighbours = self.topology.neighbourhoods(population.len(), &mut rng);
+        }
+        neighbourhoods.last_best = best;
+
+        let bests = state.borrow::<BestParticles<P>>();
+        let mut velocities = state.borrow_mut::<ParticleVelocities<P>>();
+
+        for (i, individual) in population.iter_mut().enumerate() {
+            let leader = *neighbourhoods.neighbours[i]
+                .iter()
+                .min_by(|&&a, &&b| bests[a].objective().value().total_cmp(&bests[b].objective().value()))
+                .expect("every neighbourhood contains the particle itself");
+            let personal_best = bests[i].solution();
+            let neighbourhood_best = bests[leader].solution();
+
+            let position: Vec<f64> = individual
+                .solution()
+                .iter()
+                .zip(velocities[i].iter_mut())
+                .enumerate()
+                .map(|(d, (x, v))| {
+                    let r1: f64 = rng.gen();
+                    let r2: f64 = rng.gen();
+                    *v = (w * *v
+                        + c1 * r1 * (personal_best[d] - x)
+                        + c2 * r2 * (neighbourhood_best[d] - x))
+                        .clamp(-self.v_max.0[d], self.v_max.0[d]);
+                    x + *v
+                })
+                .collect();
+            *individual = Individual::new_unevaluated(position);
+        }
+        Ok(())
+    }
+}
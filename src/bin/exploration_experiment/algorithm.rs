@@ -0,0 +1,909 @@
+use std::ops::Range;
+
+use anyhow::{bail, Context};
+use clap::{Args, Subcommand};
+use mahf::{configuration::Configuration, logging::config::LogConfig, prelude::*};
+use mahf_coco::Instance;
+use serde::Serialize;
+
+use crate::algorithms::exploration_pso::{exploration_pso, ExplorationMechanism, NewPopAdaptationParams, ParticlePolicy};
+use crate::algorithms::bounds::BoundaryViolations;
+use crate::algorithms::cmaes::cma_es;
+use crate::algorithms::jso::{jso, JsoParams};
+use crate::algorithms::lshade::{lshade, LshadeParams};
+use crate::algorithms::parameters::{
+    check_new_pop, BoundaryArgs, Crossover, DiversityMeasure, ExplorationArgs, InitializationArgs, NewPopAdaptationArgs, ParticlePolicyArgs, PortfolioArgs, ReferenceSolution, Replacement,
+    RestartArgs, RestartStrategy, SwarmArgs, TerminationType, UpdateRuleArgs,
+};
+use crate::algorithms::portfolio::{ArmRewards, ArmSelections};
+use crate::algorithms::pso::basic_pso;
+use crate::algorithms::restart::{LastRestart, RestartCount, RestartParams};
+use crate::algorithms::velocity::UpdateRule;
+use crate::algorithms::shade::{exploration_shade, shade};
+
+/// Parameters shared by all PSO variants.
+#[derive(Args, Serialize)]
+pub struct PsoArgs {
+    /// Population size of algorithm
+    #[arg(long, default_value_t = 50)]
+    population_size: u32,
+
+    /// Inertia weight of PSO; 0.0 to 1.0
+    #[arg(long, default_value_t = 0.5034)]
+    inertia_weight: f64,
+
+    /// C1 of PSO; 0.0 to 2.5
+    #[arg(long, default_value_t = 1.0519)]
+    c1: f64,
+
+    /// C2 of PSO; 0.0 to 2.5
+    #[arg(long, default_value_t = 2.3403)]
+    c2: f64,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    swarm: SwarmArgs,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    boundary: BoundaryArgs,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    initialization: InitializationArgs,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    update_rule: UpdateRuleArgs,
+}
+
+impl PsoArgs {
+    fn rule(&self) -> anyhow::Result<UpdateRule> {
+        self.update_rule.rule(self.inertia_weight, self.c1, self.c2)
+    }
+
+    fn description(&self) -> String {
+        let description = format!("{}_{}_{}_{}", self.population_size, self.inertia_weight, self.c1, self.c2);
+        description
+            + &self.swarm.description()
+            + &self.update_rule.description()
+            + &self.boundary.description()
+            + &self.initialization.description()
+    }
+}
+
+/// Parameters shared by all SHADE variants.
+#[derive(Args, Serialize)]
+pub struct ShadeArgs {
+    /// Population size of algorithm
+    #[arg(long, default_value_t = 50)]
+    population_size: u32,
+
+    /// Number of difference vectors; 1 or 2
+    #[arg(long, default_value_t = 1)]
+    y: u32,
+
+    /// Crossover operator; bin or exp
+    #[arg(long, value_enum, default_value_t = Crossover::Exp)]
+    crossover: Crossover,
+
+    /// History size, 1 to maximum number of iterations; tuning in 1 to 1000
+    #[arg(long, default_value_t = 297)]
+    history: usize,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    boundary: BoundaryArgs,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    initialization: InitializationArgs,
+}
+
+impl ShadeArgs {
+    /// Remaining parameters according to Tanabe and Fukunaga 2013; returns `(p_min, max_archive, f)`.
+    fn constants(&self) -> (f64, usize, f64) {
+        (2.0 / self.population_size as f64, self.population_size as usize, 0.5)
+    }
+
+    fn description(&self) -> String {
+        let (p_min, max_archive, f) = self.constants();
+        let description = format!(
+            "{}_{}_{}_{}_{}_{}_{}",
+            self.population_size, self.y, p_min, max_archive, self.history, f, self.crossover,
+        );
+        description + &self.boundary.description() + &self.initialization.description()
+    }
+}
+
+/// Parameters of L-SHADE, with the defaults of Tanabe and Fukunaga 2014.
+#[derive(Args, Serialize)]
+pub struct LshadeArgs {
+    /// Initial population size as multiple of the dimensions, reduced linearly to 4 over the evaluation budget
+    #[arg(long, default_value_t = 18.0)]
+    population_factor: f64,
+
+    /// History size of the memory of F and CR
+    #[arg(long, default_value_t = 6)]
+    history: usize,
+
+    /// Archive size relative to the population size
+    #[arg(long, default_value_t = 2.6)]
+    archive_rate: f64,
+
+    /// Minimum fraction of the best solutions the pbest is chosen from, drawn up to 0.2 as in SHADE instead of the
+    /// fixed p of L-SHADE; 0.0 to 0.2
+    #[arg(long, default_value_t = 0.11)]
+    p: f64,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    boundary: BoundaryArgs,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    initialization: InitializationArgs,
+}
+
+impl LshadeArgs {
+    fn params(&self, dimensions: usize) -> LshadeParams {
+        LshadeParams::lshade(dimensions, self.population_factor, self.history, self.archive_rate, self.p)
+    }
+
+    fn description(&self) -> String {
+        let description = format!("{}_{}_{}_{}", self.population_factor, self.history, self.archive_rate, self.p);
+        description + &self.boundary.description() + &self.initialization.description()
+    }
+}
+
+/// Parameters of jSO, with the defaults of Brest, Maučec and Bošković 2017.
+#[derive(Args, Serialize)]
+pub struct JsoArgs {
+    /// History size of the memory of F and CR, including the entry fixed to 0.9; at least 2
+    #[arg(long, default_value_t = 5)]
+    history: usize,
+
+    /// Archive size relative to the population size
+    #[arg(long, default_value_t = 1.0)]
+    archive_rate: f64,
+
+    /// Initial fraction of the best solutions the pbest is chosen from, decreased linearly to half this value;
+    /// 0.0 to 1.0
+    #[arg(long, default_value_t = 0.25)]
+    p_max: f64,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    boundary: BoundaryArgs,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    initialization: InitializationArgs,
+}
+
+impl JsoArgs {
+    fn params(&self, dimensions: usize) -> JsoParams {
+        JsoParams::new(dimensions, self.history, self.archive_rate, self.p_max)
+    }
+
+    fn description(&self) -> String {
+        let description = format!("{}_{}_{}", self.history, self.archive_rate, self.p_max);
+        description + &self.boundary.description() + &self.initialization.description()
+    }
+}
+
+/// Parameters of CMA-ES with restarts.
+#[derive(Args, Serialize)]
+pub struct CmaEsArgs {
+    /// Restart strategy; ipop or bipop
+    #[arg(long, value_enum, default_value_t = RestartStrategy::Bipop)]
+    restarts: RestartStrategy,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    boundary: BoundaryArgs,
+}
+
+impl CmaEsArgs {
+    fn description(&self) -> String {
+        self.restarts.to_string() + &self.boundary.description()
+    }
+}
+
+// Tuned parameters of the single mechanisms, used as defaults of their arguments and for the arms of the portfolio.
+const NPGM_NEW_POP: u32 = 42;
+const NPGM_REPLACEMENT: Replacement = Replacement::Random;
+const GPGM_NEW_POP: u32 = 39;
+const GPGM_REPLACEMENT: Replacement = Replacement::Random;
+const GPGM_MU: f64 = 356912675.0;
+const SRM_NEW_POP: u32 = 28;
+const SRM_REPLACEMENT: Replacement = Replacement::Worst;
+const SRM_CENTER: ReferenceSolution = ReferenceSolution::Best;
+const PDM_NEW_POP: u32 = 33;
+const PDM_REPLACEMENT: Replacement = Replacement::Worst;
+const PDM_LEADER: ReferenceSolution = ReferenceSolution::RandomSolution;
+
+/// Parameters of the New Population Generation Mechanism.
+#[derive(Args, Serialize)]
+pub struct NpgmArgs {
+    /// Population size of exploration mechanism, number of individuals that will be replaced; 1 to pop_size
+    #[arg(long, default_value_t = NPGM_NEW_POP)]
+    new_pop: u32,
+
+    /// Solutions to be replaced; best, worst or random
+    #[arg(long, value_enum, default_value_t = NPGM_REPLACEMENT)]
+    replacement: Replacement,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    adaptation: NewPopAdaptationArgs,
+}
+
+/// Parameters of the Gbest-guided Population Generation Mechanism.
+#[derive(Args, Serialize)]
+pub struct GpgmArgs {
+    /// Population size of exploration mechanism, number of individuals that will be replaced; 1 to pop_size
+    #[arg(long, default_value_t = GPGM_NEW_POP)]
+    new_pop: u32,
+
+    /// Solutions to be replaced; best, worst or random
+    #[arg(long, value_enum, default_value_t = GPGM_REPLACEMENT)]
+    replacement: Replacement,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    adaptation: NewPopAdaptationArgs,
+
+    /// Magnification factor of nuclear reaction mechanism; original between 10^0 and 10^20;
+    /// smaller values (probably) lead to more exploration
+    #[arg(long, default_value_t = GPGM_MU)]
+    mu: f64,
+}
+
+/// Parameters of the Solution Replacement Mechanism.
+#[derive(Args, Serialize)]
+pub struct SrmArgs {
+    /// Population size of exploration mechanism, number of individuals that will be replaced; 1 to pop_size
+    #[arg(long, default_value_t = SRM_NEW_POP)]
+    new_pop: u32,
+
+    /// Solutions to be replaced; best, worst or random
+    #[arg(long, value_enum, default_value_t = SRM_REPLACEMENT)]
+    replacement: Replacement,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    adaptation: NewPopAdaptationArgs,
+
+    /// Solution to be used as center; best, random_new or random_solution
+    #[arg(long, value_enum, default_value_t = SRM_CENTER)]
+    center: ReferenceSolution,
+}
+
+/// Parameters of the Population Dispersion Mechanism.
+#[derive(Args, Serialize)]
+pub struct PdmArgs {
+    /// Population size of exploration mechanism, number of individuals that will be replaced; 1 to pop_size
+    #[arg(long, default_value_t = PDM_NEW_POP)]
+    new_pop: u32,
+
+    /// Solutions to be replaced; best, worst or random
+    #[arg(long, value_enum, default_value_t = PDM_REPLACEMENT)]
+    replacement: Replacement,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    adaptation: NewPopAdaptationArgs,
+
+    /// Solution to be used as leader; best, random_new or random_solution
+    #[arg(long, value_enum, default_value_t = PDM_LEADER)]
+    leader: ReferenceSolution,
+}
+
+/// Algorithms available for the comparison, with their tuned parameters as defaults.
+///
+/// The mechanism parameters were tuned for PSO; the SHADE variants use the same defaults. The baselines L-SHADE,
+/// jSO and CMA-ES default to the parameters of their original publications; L-SHADE draws the pbest fraction as in
+/// SHADE, and all baselines use the boundary handling of the other algorithms.
+#[derive(Subcommand, Serialize)]
+#[serde(tag = "algorithm", rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// Particle Swarm Optimization
+    Pso {
+        #[command(flatten)]
+        #[serde(flatten)]
+        pso: PsoArgs,
+    },
+    /// PSO with random restarts
+    PsoRr {
+        #[command(flatten)]
+        #[serde(flatten)]
+        pso: PsoArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        restart: RestartArgs,
+    },
+    /// PSO with New Population Generation Mechanism
+    PsoNpgm {
+        #[command(flatten)]
+        #[serde(flatten)]
+        pso: PsoArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        npgm: NpgmArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        particles: ParticlePolicyArgs,
+    },
+    /// PSO with Gbest-guided Population Generation Mechanism
+    PsoGpgm {
+        #[command(flatten)]
+        #[serde(flatten)]
+        pso: PsoArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        gpgm: GpgmArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        particles: ParticlePolicyArgs,
+    },
+    /// PSO with Solution Replacement Mechanism
+    PsoSrm {
+        #[command(flatten)]
+        #[serde(flatten)]
+        pso: PsoArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        srm: SrmArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        particles: ParticlePolicyArgs,
+    },
+    /// PSO with Population Dispersion Mechanism
+    PsoPdm {
+        #[command(flatten)]
+        #[serde(flatten)]
+        pso: PsoArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        pdm: PdmArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        particles: ParticlePolicyArgs,
+    },
+    /// PSO with a portfolio of random restarts, NPGM, GPGM, SRM and PDM with their tuned parameters,
+    /// selecting a mechanism by credit assignment each time the exploration is triggered
+    PsoPortfolio {
+        #[command(flatten)]
+        #[serde(flatten)]
+        pso: PsoArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        portfolio: PortfolioArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        particles: ParticlePolicyArgs,
+    },
+    /// Success-History based Adaptive Differential Evolution
+    Shade {
+        #[command(flatten)]
+        #[serde(flatten)]
+        shade: ShadeArgs,
+    },
+    /// SHADE with random restarts
+    ShadeRr {
+        #[command(flatten)]
+        #[serde(flatten)]
+        shade: ShadeArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        restart: RestartArgs,
+    },
+    /// SHADE with New Population Generation Mechanism
+    ShadeNpgm {
+        #[command(flatten)]
+        #[serde(flatten)]
+        shade: ShadeArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        npgm: NpgmArgs,
+    },
+    /// SHADE with Gbest-guided Population Generation Mechanism
+    ShadeGpgm {
+        #[command(flatten)]
+        #[serde(flatten)]
+        shade: ShadeArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        gpgm: GpgmArgs,
+    },
+    /// SHADE with Solution Replacement Mechanism
+    ShadeSrm {
+        #[command(flatten)]
+        #[serde(flatten)]
+        shade: ShadeArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        srm: SrmArgs,
+    },
+    /// SHADE with Population Dispersion Mechanism
+    ShadePdm {
+        #[command(flatten)]
+        #[serde(flatten)]
+        shade: ShadeArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        pdm: PdmArgs,
+    },
+    /// SHADE with a portfolio of random restarts, NPGM, GPGM, SRM and PDM,
+    /// selecting a mechanism by credit assignment each time the exploration is triggered
+    ShadePortfolio {
+        #[command(flatten)]
+        #[serde(flatten)]
+        shade: ShadeArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        exploration: ExplorationArgs,
+
+        #[command(flatten)]
+        #[serde(flatten)]
+        portfolio: PortfolioArgs,
+    },
+    /// SHADE with linear population size reduction
+    Lshade {
+        #[command(flatten)]
+        #[serde(flatten)]
+        lshade: LshadeArgs,
+    },
+    /// jSO, an improved L-SHADE
+    Jso {
+        #[command(flatten)]
+        #[serde(flatten)]
+        jso: JsoArgs,
+    },
+    /// CMA-ES with IPOP or BIPOP restarts
+    CmaEs {
+        #[command(flatten)]
+        #[serde(flatten)]
+        cma_es: CmaEsArgs,
+    },
+}
+
+/// Base algorithm of a variant.
+#[derive(Clone, Copy)]
+enum Base<'a> {
+    /// PSO, with the handling of replaced particles if the variant replaces particles.
+    Pso(&'a PsoArgs, Option<&'a ParticlePolicyArgs>),
+    Shade(&'a ShadeArgs),
+    Lshade(&'a LshadeArgs),
+    Jso(&'a JsoArgs),
+    CmaEs(&'a CmaEsArgs),
+}
+
+/// Exploration mechanism of a variant.
+enum Mechanism<'a> {
+    RandomRestart(&'a RestartArgs),
+    Npgm(&'a NpgmArgs),
+    Gpgm(&'a GpgmArgs),
+    Srm(&'a SrmArgs),
+    Pdm(&'a PdmArgs),
+    Portfolio(&'a PortfolioArgs),
+}
+
+impl Mechanism<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            Mechanism::RandomRestart(_) => "RR",
+            Mechanism::Npgm(_) => "NPGM",
+            Mechanism::Gpgm(_) => "GPGM",
+            Mechanism::Srm(_) => "SRM",
+            Mechanism::Pdm(_) => "PDM",
+            Mechanism::Portfolio(_) => "PORTFOLIO",
+        }
+    }
+
+    /// Parameter values joined by `_`; empty for full random restarts.
+    fn description(&self) -> String {
+        match self {
+            Mechanism::RandomRestart(restart) => restart.description(),
+            Mechanism::Npgm(npgm) => {
+                format!("{}_{}", npgm.new_pop, npgm.replacement) + &npgm.adaptation.description()
+            }
+            Mechanism::Gpgm(gpgm) => {
+                format!("{}_{}_{}", gpgm.new_pop, gpgm.mu, gpgm.replacement) + &gpgm.adaptation.description()
+            }
+            Mechanism::Srm(srm) => {
+                format!("{}_{}_{}", srm.new_pop, srm.center, srm.replacement) + &srm.adaptation.description()
+            }
+            Mechanism::Pdm(pdm) => {
+                format!("{}_{}_{}", pdm.new_pop, pdm.leader, pdm.replacement) + &pdm.adaptation.description()
+            }
+            Mechanism::Portfolio(portfolio) => portfolio.description(),
+        }
+    }
+
+    fn mechanism(&self, evaluations: u32) -> ExplorationMechanism {
+        match self {
+            Mechanism::RandomRestart(restart) => ExplorationMechanism::RandomRestart(restart.params()),
+            Mechanism::Npgm(npgm) => ExplorationMechanism::Npgm {
+                new_pop: npgm.new_pop,
+                replacement: npgm.replacement,
+            },
+            Mechanism::Gpgm(gpgm) => ExplorationMechanism::Gpgm {
+                new_pop: gpgm.new_pop,
+                mu: gpgm.mu, // magnification factor
+                termination_type: TerminationType::Evaluations,
+                termination_value: evaluations as usize,
+                replacement: gpgm.replacement,
+            },
+            Mechanism::Srm(srm) => ExplorationMechanism::Srm {
+                new_pop: srm.new_pop,
+                center: srm.center,
+                replacement: srm.replacement,
+            },
+            Mechanism::Pdm(pdm) => ExplorationMechanism::Pdm {
+                new_pop: pdm.new_pop,
+                leader: pdm.leader,
+                replacement: pdm.replacement,
+            },
+            Mechanism::Portfolio(portfolio) => ExplorationMechanism::Portfolio {
+                arms: portfolio_arms(evaluations),
+                credit: portfolio.credit_assignment(),
+            },
+        }
+    }
+
+    /// Number of solutions replaced by the single mechanisms.
+    fn new_pop(&self) -> Option<u32> {
+        match self {
+            Mechanism::RandomRestart(_) | Mechanism::Portfolio(_) => None,
+            Mechanism::Npgm(npgm) => Some(npgm.new_pop),
+            Mechanism::Gpgm(gpgm) => Some(gpgm.new_pop),
+            Mechanism::Srm(srm) => Some(srm.new_pop),
+            Mechanism::Pdm(pdm) => Some(pdm.new_pop),
+        }
+    }
+
+    fn adaptation(&self) -> Option<NewPopAdaptationParams> {
+        match self {
+            Mechanism::RandomRestart(_) | Mechanism::Portfolio(_) => None,
+            Mechanism::Npgm(npgm) => npgm.adaptation.params(),
+            Mechanism::Gpgm(gpgm) => gpgm.adaptation.params(),
+            Mechanism::Srm(srm) => srm.adaptation.params(),
+            Mechanism::Pdm(pdm) => pdm.adaptation.params(),
+        }
+    }
+}
+
+impl Algorithm {
+    /// Base algorithm and, for the variants with exploration, the exploration condition and mechanism.
+    fn parts(&self) -> (Base<'_>, Option<(&ExplorationArgs, Mechanism<'_>)>) {
+        match self {
+            Algorithm::Pso { pso } => (Base::Pso(pso, None), None),
+            Algorithm::PsoRr { pso, exploration, restart } => {
+                (Base::Pso(pso, None), Some((exploration, Mechanism::RandomRestart(restart))))
+            }
+            Algorithm::PsoNpgm { pso, exploration, npgm, particles } => {
+                (Base::Pso(pso, Some(particles)), Some((exploration, Mechanism::Npgm(npgm))))
+            }
+            Algorithm::PsoGpgm { pso, exploration, gpgm, particles } => {
+                (Base::Pso(pso, Some(particles)), Some((exploration, Mechanism::Gpgm(gpgm))))
+            }
+            Algorithm::PsoSrm { pso, exploration, srm, particles } => {
+                (Base::Pso(pso, Some(particles)), Some((exploration, Mechanism::Srm(srm))))
+            }
+            Algorithm::PsoPdm { pso, exploration, pdm, particles } => {
+                (Base::Pso(pso, Some(particles)), Some((exploration, Mechanism::Pdm(pdm))))
+            }
+            Algorithm::PsoPortfolio { pso, exploration, portfolio, particles } => {
+                (Base::Pso(pso, Some(particles)), Some((exploration, Mechanism::Portfolio(portfolio))))
+            }
+            Algorithm::Shade { shade } => (Base::Shade(shade), None),
+            Algorithm::ShadeRr { shade, exploration, restart } => {
+                (Base::Shade(shade), Some((exploration, Mechanism::RandomRestart(restart))))
+            }
+            Algorithm::ShadeNpgm { shade, exploration, npgm } => {
+                (Base::Shade(shade), Some((exploration, Mechanism::Npgm(npgm))))
+            }
+            Algorithm::ShadeGpgm { shade, exploration, gpgm } => {
+                (Base::Shade(shade), Some((exploration, Mechanism::Gpgm(gpgm))))
+            }
+            Algorithm::ShadeSrm { shade, exploration, srm } => {
+                (Base::Shade(shade), Some((exploration, Mechanism::Srm(srm))))
+            }
+            Algorithm::ShadePdm { shade, exploration, pdm } => {
+                (Base::Shade(shade), Some((exploration, Mechanism::Pdm(pdm))))
+            }
+            Algorithm::ShadePortfolio { shade, exploration, portfolio } => {
+                (Base::Shade(shade), Some((exploration, Mechanism::Portfolio(portfolio))))
+            }
+            Algorithm::Lshade { lshade } => (Base::Lshade(lshade), None),
+            Algorithm::Jso { jso } => (Base::Jso(jso), None),
+            Algorithm::CmaEs { cma_es } => (Base::CmaEs(cma_es), None),
+        }
+    }
+
+    /// Name of the algorithm, used as name of the data folder, e.g. `PSO_NPGM` or `BIPOP_CMA_ES`.
+    pub fn name(&self) -> String {
+        let (base, exploration) = self.parts();
+        let base = match base {
+            Base::Pso(..) => "PSO".to_string(),
+            Base::Shade(_) => "SHADE".to_string(),
+            Base::Lshade(_) => "LSHADE".to_string(),
+            Base::Jso(_) => "JSO".to_string(),
+            Base::CmaEs(cma_es) => format!("{}_CMA_ES", cma_es.restarts.to_string().to_uppercase()),
+        };
+        match exploration {
+            None => base,
+            Some((_, mechanism)) => format!("{}_{}", base, mechanism.name()),
+        }
+    }
+
+    /// Parameter values joined by `_`, used in the name of the log files.
+    pub fn description(&self) -> String {
+        let (base, exploration) = self.parts();
+        let mut description = match base {
+            Base::Pso(pso, _) => pso.description(),
+            Base::Shade(shade) => shade.description(),
+            Base::Lshade(lshade) => lshade.description(),
+            Base::Jso(jso) => jso.description(),
+            Base::CmaEs(cma_es) => cma_es.description(),
+        };
+        if let Some((exploration, mechanism)) = exploration {
+            description = format!("{}_{}", description, exploration.description());
+            let mechanism = mechanism.description();
+            if !mechanism.is_empty() {
+                description = format!("{}_{}", description, mechanism);
+            }
+        }
+        if let Base::Pso(_, Some(particles)) = base {
+            description += &particles.description();
+        }
+        description
+    }
+
+    /// Checks the parameters that cannot be checked by clap, so that invalid parameters are rejected when the
+    /// arguments or the experiment file are parsed instead of failing every run.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let (base, exploration) = self.parts();
+        if let Base::Pso(pso, _) = base {
+            pso.rule()?;
+        }
+        if let Base::Jso(jso) = base {
+            if jso.history < 2 {
+                bail!("jSO needs at least one memory entry besides the one fixed to 0.9 (--history >= 2)");
+            }
+        }
+        if let Some((_, mechanism)) = &exploration {
+            let population_size = match base {
+                Base::Pso(pso, _) => pso.population_size,
+                Base::Shade(shade) => shade.population_size,
+                Base::Lshade(_) | Base::Jso(_) | Base::CmaEs(_) => unreachable!("the baselines have no exploration"),
+            };
+            if let Some(new_pop) = mechanism.new_pop() {
+                check_new_pop(new_pop, population_size)?;
+            }
+            if let Mechanism::Portfolio(portfolio) = mechanism {
+                // the arms do not depend on the budget except for the termination of GPGM
+                let arms = portfolio_arms(0);
+                portfolio.check_arms(arms.len())?;
+                for arm in &arms {
+                    if let Some(new_pop) = arm.new_pop() {
+                        check_new_pop(new_pop, population_size)
+                            .with_context(|| format!("invalid portfolio arm {}", arm.name()))?;
+                    }
+                }
+            }
+        }
+        if let (Base::Shade(_), Some((_, Mechanism::RandomRestart(restart)))) = (base, exploration) {
+            if restart.restart_growth != 1.0 {
+                bail!("population growth on restart (--restart-growth) is only supported for PSO");
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the configuration of the algorithm for a problem with the given domain, computing the `diversity`
+    /// measures in addition to the minimum individual distance.
+    pub fn configuration(
+        &self,
+        evaluations: u32,
+        domain: &[Range<f64>],
+        diversity: &[DiversityMeasure],
+    ) -> anyhow::Result<Configuration<Instance>> {
+        let configuration = match self.parts() {
+            (Base::Pso(pso, _), None) => basic_pso(
+                evaluations,
+                pso.population_size,
+                pso.rule()?, // inertia weight and acceleration coefficients
+                pso.swarm.v_max(domain), // velocity limit per dimension
+                pso.swarm.topology,
+                pso.boundary.boundary, // handling of solutions outside the domain
+                pso.initialization.initialization, // initial population
+                diversity, // additional diversity measures
+            ),
+            (Base::Pso(pso, particles), Some((exploration, mechanism))) => exploration_pso(
+                evaluations,
+                pso.population_size,
+                pso.rule()?,
+                pso.swarm.v_max(domain),
+                pso.swarm.topology,
+                exploration.condition(evaluations), // i.e. when to explore
+                mechanism.mechanism(evaluations),
+                mechanism.adaptation(),
+                exploration.attribution_window,
+                particles.map_or_else(ParticlePolicy::default, ParticlePolicyArgs::policy),
+                pso.boundary.boundary,
+                pso.initialization.initialization,
+                diversity,
+            ),
+            (Base::Shade(shade_args), exploration) => {
+                let (p_min, max_archive, f) = shade_args.constants();
+                let cr = 0.5;
+                match exploration {
+                    None => shade(
+                        evaluations,
+                        shade_args.population_size,
+                        shade_args.y, // number of individuals to select for mutation
+                        p_min, // minimum for parameter selecting the pbest
+                        max_archive, // maximum size of archive
+                        shade_args.history, // maximum length of history for F and CR adaptation
+                        f, // initial value of F; of no consequence when using SHADEAdaptation
+                        shade_args.crossover.operator(cr), // exp or bin
+                        shade_args.boundary.boundary, // handling of solutions outside the domain
+                        shade_args.initialization.initialization, // initial population
+                        diversity, // additional diversity measures
+                    ),
+                    Some((exploration, mechanism)) => exploration_shade(
+                        evaluations,
+                        shade_args.population_size,
+                        shade_args.y,
+                        p_min,
+                        max_archive,
+                        shade_args.history,
+                        f,
+                        shade_args.crossover.operator(cr),
+                        shade_args.boundary.boundary,
+                        shade_args.initialization.initialization,
+                        diversity,
+                        exploration.condition(evaluations),
+                        mechanism.mechanism(evaluations),
+                        mechanism.adaptation(),
+                        exploration.attribution_window,
+                    ),
+                }
+            }
+            (Base::Lshade(lshade_args), _) => lshade(
+                evaluations,
+                lshade_args.params(domain.len()), // initial population size depends on the dimensions
+                lshade_args.boundary.boundary,
+                lshade_args.initialization.initialization,
+                diversity,
+            ),
+            (Base::Jso(jso_args), _) => jso(
+                evaluations,
+                jso_args.params(domain.len()), // initial population size depends on the dimensions
+                jso_args.boundary.boundary,
+                jso_args.initialization.initialization,
+                diversity,
+            ),
+            (Base::CmaEs(cma_es_args), _) => cma_es(
+                evaluations,
+                cma_es_args.restarts, // IPOP or BIPOP
+                cma_es_args.boundary.boundary,
+                diversity,
+            ),
+        };
+        Ok(configuration)
+    }
+
+    /// Adds the log entries specific to the algorithm.
+    pub fn configure_log(&self, con: &mut LogConfig<Instance>) {
+        let (base, exploration) = self.parts();
+        con.with_many(conditions::EveryN::iterations(1), [ValueOf::<BoundaryViolations>::entry()]);
+        let restarts = matches!(base, Base::CmaEs(_))
+            || matches!(exploration, Some((_, Mechanism::RandomRestart(_) | Mechanism::Portfolio(_))));
+        if restarts {
+            con.with_many(
+                conditions::EveryN::iterations(1),
+                [ValueOf::<RestartCount>::entry(), ValueOf::<LastRestart>::entry()],
+            );
+        }
+        if let Some((_, Mechanism::Portfolio(_))) = exploration {
+            con.with_many(
+                conditions::EveryN::iterations(1),
+                [ValueOf::<ArmSelections>::entry(), ValueOf::<ArmRewards>::entry()],
+            );
+        }
+    }
+}
+
+/// Arms of the mechanism portfolio, in the order of the logged selections and rewards, with the tuned parameters
+/// of the single mechanisms.
+fn portfolio_arms(evaluations: u32) -> Vec<ExplorationMechanism> {
+    vec![
+        ExplorationMechanism::RandomRestart(RestartParams::default()),
+        ExplorationMechanism::Npgm {
+            new_pop: NPGM_NEW_POP,
+            replacement: NPGM_REPLACEMENT,
+        },
+        ExplorationMechanism::Gpgm {
+            new_pop: GPGM_NEW_POP,
+            mu: GPGM_MU,
+            termination_type: TerminationType::Evaluations,
+            termination_value: evaluations as usize,
+            replacement: GPGM_REPLACEMENT,
+        },
+        ExplorationMechanism::Srm {
+            new_pop: SRM_NEW_POP,
+            center: SRM_CENTER,
+            replacement: SRM_REPLACEMENT,
+        },
+        ExplorationMechanism::Pdm {
+            new_pop: PDM_NEW_POP,
+            leader: PDM_LEADER,
+            replacement: PDM_REPLACEMENT,
+        },
+    ]
+}
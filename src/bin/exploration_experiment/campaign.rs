@@ -0,0 +1,161 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use anyhow::{bail, Context};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+use crate::algorithm::Algorithm;
+use crate::algorithms::parameters::DiversityMeasure;
+use crate::harness::{self, deserialize_indices, Budget, Experiment};
+
+/// Comparison campaign as described by an experiment file.
+///
+/// The algorithms are given as tables with an `algorithm` key naming the subcommand of `explore run`
+/// and the parameters as further keys; parameters that are not given take the same defaults as on the command line.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Campaign<A = toml::Table> {
+    /// Name of the campaign; the results are written to `data/<name>`.
+    pub name: String,
+    /// Numbers of the BBOB functions, given as array or as string of numbers and ranges, e.g. `"1-24"`.
+    #[serde(deserialize_with = "deserialize_indices")]
+    pub functions: Vec<usize>,
+    pub dimensions: Vec<usize>,
+    /// Instance numbers, given as array or as string of numbers and ranges, e.g. `"1-5"`.
+    #[serde(default = "default_instances", deserialize_with = "deserialize_indices")]
+    pub instances: Vec<usize>,
+    /// Number of runs per instance.
+    #[serde(default = "default_runs")]
+    pub runs: usize,
+    /// Number of evaluations per dimension.
+    #[serde(default = "default_budget")]
+    pub budget: u32,
+    /// Absolute number of evaluations, regardless of the dimensions; replaces `budget`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evaluations: Option<u32>,
+    /// Diversity measures logged in addition to the minimum individual distance.
+    #[serde(default)]
+    pub diversity: Vec<DiversityMeasure>,
+    pub algorithms: Vec<A>,
+}
+
+fn default_instances() -> Vec<usize> {
+    (1..6).collect()
+}
+
+fn default_runs() -> usize {
+    25
+}
+
+fn default_budget() -> u32 {
+    10000
+}
+
+/// Parses the parameter table of an algorithm like the arguments of `explore run`.
+#[derive(Parser)]
+#[command(no_binary_name = true)]
+struct AlgorithmParser {
+    #[command(subcommand)]
+    algorithm: Algorithm,
+}
+
+fn parse_algorithm(table: &toml::Table) -> anyhow::Result<Algorithm> {
+    let Some(name) = table.get("algorithm").and_then(toml::Value::as_str) else {
+        bail!("algorithm entry without `algorithm` name: {}", table);
+    };
+
+    let mut args = vec![name.to_string()];
+    for (key, value) in table.iter().filter(|(key, _)| *key != "algorithm") {
+        let value = match value {
+            toml::Value::String(value) => value.clone(),
+            toml::Value::Integer(value) => value.to_string(),
+            toml::Value::Float(value) => value.to_string(),
+            toml::Value::Boolean(value) => value.to_string(),
+            _ => bail!("unsupported value for parameter `{}` of `{}`: {}", key, name, value),
+        };
+        args.push(format!("--{}", key.replace('_', "-")));
+        args.push(value);
+    }
+
+    let parsed = AlgorithmParser::try_parse_from(&args)
+        .with_context(|| format!("invalid parameters for `{}`", name))?;
+    parsed.algorithm.validate().with_context(|| format!("invalid parameters for `{}`", name))?;
+    Ok(parsed.algorithm)
+}
+
+impl Campaign {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let spec = fs::read_to_string(path)
+            .with_context(|| format!("failed to read experiment file {}", path.display()))?;
+        toml::from_str(&spec)
+            .with_context(|| format!("failed to parse experiment file {}", path.display()))
+    }
+
+    /// Parses the algorithms, filling in the default values of all parameters that are not given.
+    pub fn resolve(self) -> anyhow::Result<Campaign<Algorithm>> {
+        let algorithms = self
+            .algorithms
+            .iter()
+            .map(parse_algorithm)
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Campaign {
+            name: self.name,
+            functions: self.functions,
+            dimensions: self.dimensions,
+            instances: self.instances,
+            runs: self.runs,
+            budget: self.budget,
+            evaluations: self.evaluations,
+            diversity: self.diversity,
+            algorithms,
+        })
+    }
+}
+
+impl Campaign<Algorithm> {
+    pub fn data_dir(&self) -> PathBuf {
+        PathBuf::from("data").join(&self.name)
+    }
+
+    /// Expands the campaign into one experiment per algorithm, function and dimension; with `force`, completed runs
+    /// are recomputed.
+    pub fn experiments(&self, force: bool) -> Vec<Experiment<'_>> {
+        let mut experiments = Vec::new();
+        for algorithm in &self.algorithms {
+            for &function in &self.functions {
+                for &dimensions in &self.dimensions {
+                    experiments.push(Experiment {
+                        data_dir: self.data_dir(),
+                        algorithm,
+                        function,
+                        dimensions,
+                        instances: self.instances.clone(),
+                        runs: self.runs,
+                        budget: match self.evaluations {
+                            Some(evaluations) => Budget::Absolute(evaluations),
+                            None => Budget::PerDimension(self.budget),
+                        },
+                        diversity: self.diversity.clone(),
+                        force,
+                    });
+                }
+            }
+        }
+        experiments
+    }
+
+    /// Writes the resolved campaign to `campaign.toml` in the data folder and runs all experiments in one job queue,
+    /// skipping completed runs unless `force` is set.
+    ///
+    /// Failed runs do not stop the campaign; an error is returned once all runs finished.
+    pub fn run(&self, force: bool) -> anyhow::Result<()> {
+        let data_dir = self.data_dir();
+        fs::create_dir_all(&data_dir)?;
+
+        let resolved = toml::to_string(self).context("failed to serialize resolved campaign")?;
+        fs::write(data_dir.join("campaign.toml"), resolved)?;
+
+        harness::run(&self.experiments(force)).with_context(|| format!("campaign `{}` had failed runs", self.name))
+    }
+}
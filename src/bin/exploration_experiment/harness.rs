@@ -0,0 +1,582 @@
+use mahf::{lens::common::BestObjectiveValueLens, prelude::*, Random};
+use mahf_coco::{backends::C, AcceleratedEvaluator, Context, Name::Bbob, Options, Suite};
+
+use anyhow::{anyhow, bail, Context as _};
+use clap::Args;
+use mahf::components::measures::diversity::{MinimumIndividualDistance, NormalizedDiversityLens};
+use mahf::problems::LimitedVectorProblem;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use std::{
+    any::Any,
+    fs::{self},
+    io::{BufWriter, Write},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::algorithm::Algorithm;
+use crate::algorithms::diversity::Diversities;
+use crate::algorithms::events::MechanismEvents;
+use crate::algorithms::parameters::DiversityMeasure;
+
+static CONTEXT: Lazy<Context<C>> = Lazy::new(Context::default);
+
+/// Numbers of functions, instances or CPUs given as list of numbers and inclusive ranges, e.g. `1-5` or `1,3,10-15`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Indices(pub Vec<usize>);
+
+impl FromStr for Indices {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut indices = Vec::new();
+        for part in s.split(',').map(str::trim) {
+            let number = |n: &str| n.trim().parse::<usize>().map_err(|_| format!("invalid number `{}` in `{}`", n, s));
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (number(start)?, number(end)?);
+                    if start > end {
+                        return Err(format!("empty range `{}`", part));
+                    }
+                    indices.extend(start..=end);
+                }
+                None => indices.push(number(part)?),
+            }
+        }
+        Ok(Self(indices))
+    }
+}
+
+/// Deserializes function or instance numbers from either an array of numbers or a string of numbers and ranges,
+/// see [`Indices`].
+pub fn deserialize_indices<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<usize>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Spec {
+        List(Vec<usize>),
+        Ranges(String),
+    }
+
+    match Spec::deserialize(deserializer)? {
+        Spec::List(indices) => Ok(indices),
+        Spec::Ranges(ranges) => ranges.parse::<Indices>().map(|indices| indices.0).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Evaluation budget of a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Budget {
+    /// Evaluations per dimension.
+    PerDimension(u32),
+    /// Evaluations regardless of the dimension.
+    Absolute(u32),
+}
+
+impl Default for Budget {
+    /// 10000 evaluations per dimension.
+    fn default() -> Self {
+        Budget::PerDimension(10000)
+    }
+}
+
+impl Budget {
+    pub fn evaluations(self, dimensions: usize) -> u32 {
+        match self {
+            Budget::PerDimension(evaluations) => evaluations * dimensions as u32,
+            Budget::Absolute(evaluations) => evaluations,
+        }
+    }
+
+    /// Suffix of the log file names; empty for the default budget, which was the only one before.
+    fn description(self) -> String {
+        match self {
+            budget if budget == Budget::default() => String::new(),
+            Budget::PerDimension(evaluations) => format!("_budget{}d", evaluations),
+            Budget::Absolute(evaluations) => format!("_budget{}", evaluations),
+        }
+    }
+}
+
+/// Thread pool running the jobs.
+#[derive(Args)]
+pub struct Threads {
+    /// Number of worker threads; defaults to the CPUs of the SLURM job if set, otherwise to the number of pinned CPUs
+    /// or all CPUs
+    #[arg(long, global = true, env = "SLURM_CPUS_PER_TASK")]
+    threads: Option<usize>,
+
+    /// Pin the worker threads to these CPUs, given as list of numbers and ranges, e.g. "0-11"; the threads are
+    /// assigned to the CPUs in turn
+    #[arg(long, global = true)]
+    pin: Option<Indices>,
+}
+
+impl Threads {
+    /// Configures the global rayon thread pool, which must happen before the first parallel iterator is used.
+    pub fn build_global(&self) -> anyhow::Result<()> {
+        let cores = match &self.pin {
+            Some(cpus) => {
+                let available = core_affinity::get_core_ids().context("failed to query the available CPUs")?;
+                let cores = cpus
+                    .0
+                    .iter()
+                    .map(|&cpu| {
+                        available
+                            .iter()
+                            .find(|core| core.id == cpu)
+                            .copied()
+                            .with_context(|| format!("CPU {} is not available", cpu))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                if cores.is_empty() {
+                    bail!("no CPUs given to --pin");
+                }
+                cores
+            }
+            None => Vec::new(),
+        };
+
+        let threads = match (self.threads, cores.len()) {
+            (Some(threads), _) => threads,
+            (None, 0) => 0,
+            (None, pinned) => pinned,
+        };
+
+        let mut builder = rayon::ThreadPoolBuilder::new().num_threads(threads);
+        if !cores.is_empty() {
+            builder = builder.start_handler(move |index| {
+                if !core_affinity::set_for_current(cores[index % cores.len()]) {
+                    eprintln!("failed to pin worker thread {} to CPU {}", index, cores[index % cores.len()].id);
+                }
+            });
+        }
+        builder.build_global().context("failed to build the thread pool")
+    }
+}
+
+/// BBOB problems the algorithm is run on.
+#[derive(Args)]
+pub struct Benchmark {
+    /// Numbers of BBOB functions as list of numbers and ranges, e.g. "1" or "1-24"
+    #[arg(long, global = true, default_value = "1")]
+    function: Indices,
+
+    /// Dimensions of BBOB functions, separated by commas
+    #[arg(long, global = true, value_delimiter = ',', default_value = "10")]
+    dimensions: Vec<usize>,
+
+    /// Instances of BBOB function as list of numbers and ranges, e.g. "1-5" or "1,3,10-15"
+    #[arg(long, global = true, default_value = "1-5")]
+    instances: Indices,
+
+    /// Number of runs per instance
+    #[arg(long, global = true, default_value_t = 25)]
+    runs: usize,
+
+    /// Number of evaluations per dimension
+    #[arg(long, global = true, default_value_t = 10000)]
+    budget: u32,
+
+    /// Absolute number of evaluations, regardless of the dimensions; replaces --budget
+    #[arg(long, global = true, conflicts_with = "budget")]
+    evaluations: Option<u32>,
+
+    /// Diversity measures logged in addition to the minimum individual distance, separated by commas;
+    /// minimum_distance, dimension_wise, pairwise, distance_to_centroid, radius or true_diversity
+    #[arg(long, global = true, value_enum, value_delimiter = ',')]
+    diversity: Vec<DiversityMeasure>,
+
+    /// Recompute runs whose results already exist instead of skipping them
+    #[arg(long, global = true)]
+    force: bool,
+}
+
+impl Benchmark {
+    /// One experiment per function and dimension, by default with 25 runs on each of the first 5 instances and
+    /// a budget of `10000 * dimensions` evaluations.
+    pub fn experiments<'a>(&self, algorithm: &'a Algorithm) -> Vec<Experiment<'a>> {
+        let mut experiments = Vec::new();
+        for &function in &self.function.0 {
+            for &dimensions in &self.dimensions {
+                experiments.push(Experiment {
+                    data_dir: PathBuf::from("data"),
+                    algorithm,
+                    function,
+                    dimensions,
+                    instances: self.instances.0.clone(),
+                    runs: self.runs,
+                    budget: match self.evaluations {
+                        Some(evaluations) => Budget::Absolute(evaluations),
+                        None => Budget::PerDimension(self.budget),
+                    },
+                    diversity: self.diversity.clone(),
+                    force: self.force,
+                });
+            }
+        }
+        experiments
+    }
+}
+
+/// All runs of one algorithm on the instances of one BBOB function.
+pub struct Experiment<'a> {
+    /// Root folder; the logs are written to `<data_dir>/<algorithm>/d<dimensions>`.
+    pub data_dir: PathBuf,
+    pub algorithm: &'a Algorithm,
+    pub function: usize,
+    pub dimensions: usize,
+    pub instances: Vec<usize>,
+    /// Number of runs per instance.
+    pub runs: usize,
+    pub budget: Budget,
+    /// Diversity measures logged in addition to the minimum individual distance.
+    pub diversity: Vec<DiversityMeasure>,
+    /// Recompute runs that are already completed.
+    pub force: bool,
+}
+
+impl Experiment<'_> {
+    /// Parameter values of the algorithm and the budget, used in the name of the log files.
+    fn description(&self) -> String {
+        self.algorithm.description() + &self.budget.description()
+    }
+
+    /// Folder of the logs, `<data_dir>/<algorithm>/d<dimensions>`.
+    fn folder(&self) -> PathBuf {
+        self.data_dir
+            .join(self.algorithm.name())
+            .join(format!("d{:?}", self.dimensions))
+    }
+}
+
+/// Writes the activations of the exploration mechanisms of a run as JSON lines, one event per line.
+fn write_events(events: &MechanismEvents, path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    for event in &events.0 {
+        serde_json::to_writer(&mut writer, event)?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Failed run of an experiment, as written to the failure report.
+#[derive(Serialize)]
+struct Failure {
+    /// Index of the experiment, which has its own report.
+    #[serde(skip)]
+    experiment: usize,
+    run: usize,
+    instance: String,
+    seed: u64,
+    error: String,
+}
+
+/// Message of a caught panic.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "panic with non-string payload".to_string(),
+        },
+    }
+}
+
+/// Seed of a run on the instance with the given number.
+///
+/// The seed depends only on the run and the instance number, not on the other instances of the experiment, so that a
+/// run is reproducible with any instance list. For the instances 1 to 5, these are the seeds of the original experiments,
+/// which already use every number as seed; the seeds of higher instances hold the instance number in the upper 32 bits
+/// instead, so that they differ from each other and from those of the instances 1 to 5.
+fn seed(run: usize, instance: usize) -> u64 {
+    if instance <= 5 {
+        ((run - 1) * 5 + instance) as u64
+    } else {
+        ((instance as u64) << 32) | run as u64
+    }
+}
+
+/// Whether the run with the output prefix `experiment_desc` is completed with the given `seed`.
+///
+/// The CBOR log is written last and moved into place only once it is complete, so its existence marks a completed run.
+/// The seed of the run is stored next to it in `<experiment_desc>.seed`; a log without it or with another seed is
+/// recomputed.
+fn completed(data_dir: &Path, experiment_desc: &str, seed: u64) -> bool {
+    data_dir.join(format!("{}.cbor", experiment_desc)).is_file()
+        && fs::read_to_string(data_dir.join(format!("{}.seed", experiment_desc)))
+            .is_ok_and(|stored| stored.trim() == seed.to_string())
+}
+
+/// Run of an algorithm on a problem instance; one item of the job queue.
+struct Job<E> {
+    /// Index of the experiment.
+    experiment: usize,
+    /// Index of the problem instance within the experiment.
+    problem: usize,
+    run: usize,
+    seed: u64,
+    evaluator: E,
+    /// Output prefix of the log files.
+    output: String,
+}
+
+/// Runs all runs of the `experiments` on each of their instances, writing the log of every run to a CBOR file.
+///
+/// All (algorithm, function, dimension, instance, run) combinations are flattened into one queue processed by the
+/// rayon thread pool, so that all cores are busy until the last runs.
+///
+/// Runs whose log already exists are skipped unless `force` is set, so an interrupted experiment can be resumed.
+///
+/// A failing run does not affect the others; after all runs finished, the failures of each experiment are written
+/// with their error and seed to `f<function>_<description>_failures.jsonl` in its data folder and an error is returned.
+pub fn run(experiments: &[Experiment]) -> anyhow::Result<()> {
+    // Start timing execution
+    let start = Instant::now();
+
+    let mut suites = Vec::new();
+    let mut problems = Vec::new();
+    let mut jobs = Vec::new();
+
+    for (e, experiment) in experiments.iter().enumerate() {
+        let algorithm = experiment.algorithm;
+
+        // set the benchmark problems; the suite yields the instances in ascending order
+        let mut numbers = experiment.instances.clone();
+        numbers.sort_unstable();
+        numbers.dedup();
+        let options = Options::new()
+            .with_dimensions([experiment.dimensions])
+            .with_function_indices([experiment.function])
+            .with_instance_indices(numbers.clone());
+        let mut suite = Suite::with_options(Bbob, None, Some(&options)).with_context(|| {
+            format!("failed to create the BBOB suite of function {} in {} dimensions", experiment.function, experiment.dimensions)
+        })?;
+
+        let mut instances = Vec::new();
+        let mut evaluators = Vec::new();
+        while let Some(instance) = suite.next() {
+            let evaluator = AcceleratedEvaluator::new(&CONTEXT, &mut suite, &instance);
+            instances.push(instance);
+            evaluators.push(evaluator);
+        }
+
+        let folder = experiment.folder();
+        let mut done = 0;
+        for run in 1..=experiment.runs {
+            for (i, ((instance, evaluator), &number)) in instances.iter().zip(&evaluators).zip(&numbers).enumerate() {
+                let output = format!("{}_{}_{}", run, instance.name(), experiment.description());
+                let seed = seed(run, number);
+                if !experiment.force && completed(&folder, &output, seed) {
+                    done += 1;
+                    continue;
+                }
+                jobs.push(Job {
+                    experiment: e,
+                    problem: i,
+                    run,
+                    seed,
+                    evaluator: evaluator.clone(),
+                    output,
+                });
+            }
+        }
+        if done > 0 {
+            println!(
+                "Skipping {} of {} completed runs of {} in {}; use --force to recompute them",
+                done,
+                experiment.runs * instances.len(),
+                algorithm.name(),
+                folder.display(),
+            );
+        }
+
+        suites.push(suite);
+        problems.push(instances);
+    }
+
+    let failures: Vec<Failure> = jobs
+        .into_par_iter()
+        .filter_map(|job| {
+            let experiment = &experiments[job.experiment];
+            let algorithm = experiment.algorithm;
+            let instance = &problems[job.experiment][job.problem];
+            let Job { run, seed, evaluator, output, .. } = job;
+
+            let run_job = || -> anyhow::Result<()> {
+                // set number of evaluations
+                let evaluations: u32 = experiment.budget.evaluations(experiment.dimensions);
+
+                // This is the main setup of the algorithm
+                let conf = algorithm.configuration(evaluations, &instance.domain(), &experiment.diversity)?;
+
+                let data_dir = experiment.folder();
+                fs::create_dir_all(&data_dir)
+                    .with_context(|| format!("failed to create {}", data_dir.display()))?;
+
+                let experiment_desc = &output;
+                let log_file = data_dir.join(format!("{}.cbor", experiment_desc));
+                let partial_log_file = data_dir.join(format!("{}.cbor.partial", experiment_desc));
+                let events_file = data_dir.join(format!("{}_events.jsonl", experiment_desc));
+                let success_file = data_dir.join(format!("{}_success.json", experiment_desc));
+                let seed_file = data_dir.join(format!("{}.seed", experiment_desc));
+
+                // This executes the algorithm
+                let setup =
+                    conf.optimize_with(instance, |state: &mut State<_>| -> ExecResult<()> {
+                        state.insert_evaluator(evaluator);
+                        state.insert(Random::new(seed));
+                        state.configure_log(|con| {
+                            con.with_many(
+                                conditions::EveryN::iterations(1),
+                                [
+                                    ValueOf::<common::Evaluations>::entry(),
+                                    BestObjectiveValueLens::entry(),
+                                    NormalizedDiversityLens::<MinimumIndividualDistance>::entry(),
+                                ],
+                            );
+                            if !experiment.diversity.is_empty() {
+                                con.with_many(conditions::EveryN::iterations(1), [ValueOf::<Diversities>::entry()]);
+                            }
+                            algorithm.configure_log(con);
+                            Ok(())
+                        })
+                    });
+
+                let results = setup.map_err(|error| anyhow!("optimization failed: {:?}", error))?;
+                if results.has::<MechanismEvents>() {
+                    let events = results.borrow::<MechanismEvents>();
+                    write_events(&events, events_file).context("failed to write mechanism events")?;
+                    let success = serde_json::to_string_pretty(&events.success())
+                        .context("failed to serialize mechanism success")?;
+                    fs::write(success_file, success).context("failed to write mechanism success")?;
+                }
+                fs::write(seed_file, seed.to_string()).context("failed to write seed")?;
+                // the log is moved into place last, marking the run as completed
+                results
+                    .log()
+                    .to_cbor(&partial_log_file)
+                    .map_err(|error| anyhow!("failed to write log: {:?}", error))?;
+                fs::rename(&partial_log_file, log_file).context("failed to move the completed log into place")?;
+
+                // Measure elapsed time
+                let duration = start.elapsed();
+
+                println!(
+                    "\n{:?}\n{}",
+                    results.best_objective_value().unwrap(),
+                    duration.as_secs_f64()
+                );
+                Ok(())
+            };
+
+            // a failed or panicking run must not abort the other runs
+            let error = match panic::catch_unwind(AssertUnwindSafe(run_job)) {
+                Ok(Ok(())) => return None,
+                Ok(Err(error)) => format!("{:#}", error),
+                Err(payload) => panic_message(payload),
+            };
+            eprintln!("run {} of {} on {} with seed {} failed: {}", run, algorithm.name(), instance.name(), seed, error);
+            Some(Failure {
+                experiment: job.experiment,
+                run,
+                instance: instance.name().to_string(),
+                seed,
+                error,
+            })
+        })
+        .collect();
+
+    let mut failed = 0;
+    for (e, experiment) in experiments.iter().enumerate() {
+        let folder = experiment.folder();
+        let report = folder.join(format!("f{}_{}_failures.jsonl", experiment.function, experiment.description()));
+        let failures: Vec<_> = failures.iter().filter(|failure| failure.experiment == e).collect();
+        if failures.is_empty() {
+            // a report of a previous attempt is obsolete once all runs succeeded
+            if report.exists() {
+                fs::remove_file(&report)?;
+            }
+            continue;
+        }
+
+        fs::create_dir_all(&folder)?;
+        let mut writer = BufWriter::new(fs::File::create(&report)?);
+        for failure in &failures {
+            serde_json::to_writer(&mut writer, failure)?;
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        eprintln!(
+            "{} of {} runs of {} failed, see {}",
+            failures.len(),
+            experiment.runs * experiment.instances.len(),
+            experiment.algorithm.name(),
+            report.display(),
+        );
+        failed += failures.len();
+    }
+    // the suites are kept alive until all runs finished
+    drop(suites);
+
+    if failed > 0 {
+        bail!("{} runs failed", failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers_and_ranges() {
+        assert_eq!("7".parse::<Indices>().unwrap(), Indices(vec![7]));
+        assert_eq!("1-5".parse::<Indices>().unwrap(), Indices(vec![1, 2, 3, 4, 5]));
+        assert_eq!("1, 3,10-12".parse::<Indices>().unwrap(), Indices(vec![1, 3, 10, 11, 12]));
+        assert_eq!("4-4".parse::<Indices>().unwrap(), Indices(vec![4]));
+    }
+
+    #[test]
+    fn seeds_of_the_original_instances_are_unchanged() {
+        assert_eq!(seed(1, 1), 1);
+        assert_eq!(seed(1, 5), 5);
+        assert_eq!(seed(2, 1), 6);
+        assert_eq!(seed(25, 5), 125);
+    }
+
+    #[test]
+    fn seeds_differ_for_instances_above_5() {
+        let mut seeds: Vec<u64> = (1..=30).flat_map(|run| (1..=15).map(move |instance| seed(run, instance))).collect();
+        let n = seeds.len();
+        seeds.sort_unstable();
+        seeds.dedup();
+        assert_eq!(seeds.len(), n);
+        assert_ne!(seed(1, 6), seed(2, 1));
+    }
+
+    #[test]
+    fn rejects_malformed_indices() {
+        for s in ["", "a", "1,", "1-", "-3", "5-1", "1-2-3", "1.5"] {
+            assert!(s.parse::<Indices>().is_err(), "`{}` should be rejected", s);
+        }
+    }
+
+    #[test]
+    fn deserializes_arrays_and_ranges() {
+        #[derive(Deserialize)]
+        struct Functions {
+            #[serde(deserialize_with = "deserialize_indices")]
+            functions: Vec<usize>,
+        }
+
+        let parse = |s: &str| toml::from_str::<Functions>(s).map(|f| f.functions);
+        assert_eq!(parse("functions = [1, 2, 24]").unwrap(), [1, 2, 24]);
+        assert_eq!(parse(r#"functions = "1-3,24""#).unwrap(), [1, 2, 3, 24]);
+        assert!(parse(r#"functions = "3-1""#).is_err());
+    }
+}
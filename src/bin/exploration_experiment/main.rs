@@ -0,0 +1,57 @@
+#[path = "../../algorithms/mod.rs"]
+mod algorithms;
+mod algorithm;
+mod campaign;
+mod harness;
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::algorithm::Algorithm;
+use crate::campaign::Campaign;
+use crate::harness::{Benchmark, Threads};
+
+#[derive(Parser)]
+#[clap(version, about)]
+struct Cli {
+    #[command(flatten)]
+    threads: Threads,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run an algorithm on the instances of BBOB functions
+    Run {
+        #[command(flatten)]
+        benchmark: Benchmark,
+
+        #[command(subcommand)]
+        algorithm: Algorithm,
+    },
+    /// Run all experiments of a comparison campaign described by a TOML file
+    Campaign {
+        /// Path of the experiment file
+        file: PathBuf,
+
+        /// Recompute runs whose results already exist instead of skipping them
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    cli.threads.build_global()?;
+
+    match cli.command {
+        Command::Run { benchmark, algorithm } => {
+            algorithm.validate()?;
+            harness::run(&benchmark.experiments(&algorithm))
+        }
+        Command::Campaign { file, force } => Campaign::from_file(&file)?.resolve()?.run(force),
+    }
+}
@@ -0,0 +1,103 @@
+#[path = "../../algorithms/mod.rs"]
+mod algorithms;
+
+use mahf::{prelude::*, configuration::Configuration, Random,
+           lens::common::{BestObjectiveValueLens},
+};
+use mahf_coco::{Instance, AcceleratedEvaluator, Suite, Context, Options, backends::C, Name::Bbob};
+
+use std::{
+    fs::{self},
+    path::PathBuf,
+    sync::{Arc},
+};
+use std::time::Instant;
+use once_cell::sync::Lazy;
+use clap::Parser;
+use crate::algorithms::cmaes::cma_es;
+use crate::algorithms::parameters::{BoundaryArgs, RestartStrategy};
+
+static CONTEXT: Lazy<Context<C>> = Lazy::new(Context::default);
+
+
+#[derive(Parser)]
+#[clap(version, about)]
+struct Args {
+    /// Seed
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    /// Instance for irace
+    #[arg(long, default_value = "1")]
+    inst: String,
+
+    /// Number of BBOB function
+    #[arg(long, default_value_t = 1)]
+    function: usize,
+
+    /// Instance of BBOB function
+    #[arg(long, default_value_t = 6)]
+    instance: usize,
+
+    /// Dimensions of BBOB function
+    #[arg(long, default_value_t = 10)]
+    dimensions: usize,
+
+    /// Restart strategy; ipop or bipop
+    #[arg(long, value_enum, default_value_t = RestartStrategy::Bipop)]
+    restarts: RestartStrategy,
+
+    #[command(flatten)]
+    boundary: BoundaryArgs,
+}
+
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let seed = args.seed;
+    let _inst = args.inst;
+    let functions = args.function;
+    let instances = args.instance;
+    let dimensions: usize = args.dimensions;
+    let restarts = args.restarts;
+    let boundary = args.boundary.boundary;
+
+    // Start timing execution
+    let start = Instant::now();
+
+    // set number of evaluations
+    let evaluations: u32 = (10000 * dimensions) as u32;
+
+    let options = Options::new()
+        .with_dimensions([dimensions])
+        .with_function_indices([functions])
+        .with_instance_indices([instances]);
+    let mut suite = Suite::with_options(Bbob, None, Some(&options)).unwrap();
+
+    while let Some(instance) = suite.next() {
+        let evaluator = AcceleratedEvaluator::new(&CONTEXT, &mut suite, &instance);
+        
+        // This is the main setup of the algorithm
+        let conf: Configuration<Instance> = cma_es(
+            evaluations,
+            restarts, // IPOP or BIPOP
+            boundary, // handling of solutions outside the domain
+            &[], // no additional diversity measures
+        );
+
+        // This executes the algorithm
+        let setup = conf.optimize_with(&instance, |state: &mut State<_>| -> ExecResult<()> {
+            state.insert_evaluator(evaluator);
+            state.insert(Random::new(seed));
+            Ok(())
+        });
+        let results = setup.unwrap();
+
+        // Measure elapsed time
+        let duration = start.elapsed();
+
+        println!("\n{:?}\n{}", results.best_objective_value().unwrap(), duration.as_secs_f64());
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -1,8 +1,7 @@
 #[path = "../../algorithms/mod.rs"]
 mod algorithms;
 
-use mahf::{prelude::*, configuration::Configuration, Random,
-           lens::common::{BestObjectiveValueLens}};
+use mahf::{prelude::*, configuration::Configuration, Random};
 use mahf_coco::{Instance, AcceleratedEvaluator, Suite, Context, Options, backends::C, Name::Bbob};
 
 use std::{
@@ -13,11 +12,9 @@ use std::{
 use std::time::Instant;
 use once_cell::sync::Lazy;
 use clap::Parser;
-use mahf::components::measures::diversity::{MinimumIndividualDistance, NormalizedDiversityLens};
-use mahf::conditions::common::PartialEqChecker;
-use mahf::prelude::common::Evaluations;
 use mahf::problems::LimitedVectorProblem;
-use crate::algorithms::pso_npgm::npgm_pso;
+use crate::algorithms::exploration_pso::{exploration_pso, ExplorationMechanism};
+use crate::algorithms::parameters::{check_new_pop, BoundaryArgs, InitializationArgs, ExplorationArgs, NewPopAdaptationArgs, ParticlePolicyArgs, Replacement, SwarmArgs, UpdateRuleArgs};
 
 static CONTEXT: Lazy<Context<C>> = Lazy::new(Context::default);
 
@@ -59,22 +56,35 @@ struct Args {
     /// C2 of PSO
     #[arg(long, default_value_t = 0.5)]
     c2: f64,
-    
-    /// Exploration condition; evaluations or diversity
-    #[arg(long, default_value = "evaluations")]
-    exploration: String,
 
-    /// Exploration parameter
-    #[arg(long, default_value_t = 0.05)]
-    exp_param: f64,
+    #[command(flatten)]
+    swarm: SwarmArgs,
+
+    #[command(flatten)]
+    boundary: BoundaryArgs,
+
+    #[command(flatten)]
+    initialization: InitializationArgs,
+
+    #[command(flatten)]
+    update_rule: UpdateRuleArgs,
+    
+    #[command(flatten)]
+    exploration: ExplorationArgs,
 
     /// Population size of exploration mechanism, number of individuals that will be replaced; 1 to pop_size
     #[arg(long, default_value_t = 5)]
     new_pop: u32,
     
     /// Solutions to be replaced; best, worst or random
-    #[arg(long, default_value = "best")]
-    replacement: String,
+    #[arg(long, value_enum, default_value_t = Replacement::Best)]
+    replacement: Replacement,
+
+    #[command(flatten)]
+    adaptation: NewPopAdaptationArgs,
+
+    #[command(flatten)]
+    particles: ParticlePolicyArgs,
 }
 
 
@@ -90,10 +100,16 @@ fn main() -> anyhow::Result<()> {
     let inertia_weight: f64 = args.inertia_weight;
     let c1: f64 = args.c1;
     let c2: f64 = args.c2;
+    let swarm = args.swarm;
+    let boundary = args.boundary.boundary;
+    let initialization = args.initialization.initialization;
+    let update_rule = args.update_rule;
     let exploration = args.exploration;
-    let exp_param: f64 = args.exp_param;
     let new_pop: u32 = args.new_pop;
+    check_new_pop(new_pop, pop_size)?;
     let replacement = args.replacement;
+    let adaptation = args.adaptation;
+    let policy = args.particles.policy();
     
     // Start timing execution
     let start = Instant::now();
@@ -110,37 +126,28 @@ fn main() -> anyhow::Result<()> {
     while let Some(instance) = suite.next() {
         let evaluator = AcceleratedEvaluator::new(&CONTEXT, &mut suite, &instance);
 
-        let bounds = instance.domain();
-        let lower = bounds[0].start.clone();
-        let upper = bounds[0].end.clone();
-        let v_max = (upper - lower) / 2.0;
-
-        let condition = if exploration == "evaluations" {
-            let eval_interval = exp_param * evaluations as f64;
-            conditions::StagnationForN::new(eval_interval as usize, ValueOf::<Evaluations>::new(), BestObjectiveValueLens::new(), PartialEqChecker::new())
-        } else {
-            conditions::LessThanN::new(exp_param, NormalizedDiversityLens::<MinimumIndividualDistance>::new())
-        };
-
-        let replacement_operator = if replacement == "best" {
-            replacement::pso::ReplaceNBestPSO::new(new_pop, v_max)
-        } else if replacement == "worst" {
-            replacement::pso::ReplaceNWorstPSO::new(new_pop, v_max)
-        } else {
-            replacement::pso::ReplaceNRandomPSO::new(new_pop, v_max)
-        };
+        let v_max = swarm.v_max(&instance.domain());
+
+        let condition = exploration.condition(evaluations);
 
         // This is the main setup of the algorithm
-        let conf: Configuration<Instance> = npgm_pso(
+        let conf: Configuration<Instance> = exploration_pso(
             evaluations,
             pop_size,
-            inertia_weight, // Weight
-            c1, // C1
-            c2, // C2
+            update_rule.rule(inertia_weight, c1, c2)?, // inertia weight and acceleration coefficients
             v_max,
+            swarm.topology, // neighbourhood topology
             condition,
-            new_pop,
-            replacement_operator,
+            ExplorationMechanism::Npgm {
+                new_pop, // number of new solutions the exploration mechanism generates
+                replacement, // replacement operator applied after exploration mechanism
+            },
+            adaptation.params(),
+            exploration.attribution_window, // iterations in which a new global best counts as success
+            policy, // handling of replaced particles
+            boundary, // handling of solutions outside the domain
+            initialization, // initial population
+            &[], // no additional diversity measures
         );
 
         // This executes the algorithm
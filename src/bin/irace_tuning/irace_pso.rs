@@ -9,6 +9,7 @@ use once_cell::sync::Lazy;
 use clap::Parser;
 use mahf::problems::LimitedVectorProblem;
 use crate::algorithms::pso::basic_pso;
+use crate::algorithms::parameters::{BoundaryArgs, InitializationArgs, SwarmArgs, UpdateRuleArgs};
 
 static CONTEXT: Lazy<Context<C>> = Lazy::new(Context::default);
 
@@ -50,6 +51,18 @@ struct Args {
     /// C2 of PSO; 0.0 to 2.5
     #[arg(long, default_value_t = 0.5)]
     c2: f64,
+
+    #[command(flatten)]
+    swarm: SwarmArgs,
+
+    #[command(flatten)]
+    boundary: BoundaryArgs,
+
+    #[command(flatten)]
+    initialization: InitializationArgs,
+
+    #[command(flatten)]
+    update_rule: UpdateRuleArgs,
 }
 
 
@@ -65,6 +78,10 @@ fn main() -> anyhow::Result<()> {
     let inertia_weight: f64 = args.inertia_weight;
     let c1: f64 = args.c1;
     let c2: f64 = args.c2;
+    let swarm = args.swarm;
+    let boundary = args.boundary.boundary;
+    let initialization = args.initialization.initialization;
+    let update_rule = args.update_rule;
     
     // Start timing execution
     let start = Instant::now();
@@ -81,19 +98,18 @@ fn main() -> anyhow::Result<()> {
     while let Some(instance) = suite.next() {
         let evaluator = AcceleratedEvaluator::new(&CONTEXT, &mut suite, &instance);
 
-        let bounds = instance.domain();
-        let lower = bounds[0].start.clone();
-        let upper = bounds[0].end.clone();
-        let v_max = (upper - lower) / 2.0;
+        let v_max = swarm.v_max(&instance.domain());
 
         // This is the main setup of the algorithm
         let conf: Configuration<Instance> = basic_pso(
             evaluations,
             pop_size,
-            inertia_weight, // Weight
-            c1, // C1
-            c2, // C2
+            update_rule.rule(inertia_weight, c1, c2)?, // inertia weight and acceleration coefficients
             v_max,
+            swarm.topology, // neighbourhood topology
+            boundary, // handling of solutions outside the domain
+            initialization, // initial population
+            &[], // no additional diversity measures
         );
 
         // This executes the algorithm
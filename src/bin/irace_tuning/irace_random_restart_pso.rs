@@ -1,8 +1,7 @@
 #[path = "../../algorithms/mod.rs"]
 mod algorithms;
 
-use mahf::{prelude::*, configuration::Configuration, Random,
-           lens::common::{BestObjectiveValueLens}};
+use mahf::{prelude::*, configuration::Configuration, Random};
 use mahf_coco::{Instance, AcceleratedEvaluator, Suite, Context, Options, backends::C, Name::Bbob};
 
 use std::{
@@ -13,11 +12,9 @@ use std::{
 use std::time::Instant;
 use once_cell::sync::Lazy;
 use clap::Parser;
-use mahf::components::measures::diversity::{MinimumIndividualDistance, NormalizedDiversityLens};
-use mahf::conditions::common::PartialEqChecker;
-use mahf::prelude::common::Evaluations;
 use mahf::problems::LimitedVectorProblem;
-use crate::algorithms::pso_rr::random_restart_pso;
+use crate::algorithms::exploration_pso::{exploration_pso, ExplorationMechanism, ParticlePolicy};
+use crate::algorithms::parameters::{BoundaryArgs, InitializationArgs, ExplorationArgs, RestartArgs, SwarmArgs, UpdateRuleArgs};
 
 static CONTEXT: Lazy<Context<C>> = Lazy::new(Context::default);
 
@@ -60,13 +57,23 @@ struct Args {
     #[arg(long, default_value_t = 0.5)]
     c2: f64,
 
-    /// Exploration condition; evaluations or diversity
-    #[arg(long, default_value = "evaluations")]
-    exploration: String,
+    #[command(flatten)]
+    swarm: SwarmArgs,
 
-    /// Exploration parameter
-    #[arg(long, default_value_t = 0.05)]
-    exp_param: f64,
+    #[command(flatten)]
+    boundary: BoundaryArgs,
+
+    #[command(flatten)]
+    initialization: InitializationArgs,
+
+    #[command(flatten)]
+    update_rule: UpdateRuleArgs,
+
+    #[command(flatten)]
+    exploration: ExplorationArgs,
+
+    #[command(flatten)]
+    restart: RestartArgs,
 }
 
 
@@ -82,8 +89,12 @@ fn main() -> anyhow::Result<()> {
     let inertia_weight: f64 = args.inertia_weight;
     let c1: f64 = args.c1;
     let c2: f64 = args.c2;
+    let swarm = args.swarm;
+    let boundary = args.boundary.boundary;
+    let initialization = args.initialization.initialization;
+    let update_rule = args.update_rule;
     let exploration = args.exploration;
-    let exp_param: f64 = args.exp_param;
+    let restart = args.restart.params();
     
     // Start timing execution
     let start = Instant::now();
@@ -100,27 +111,25 @@ fn main() -> anyhow::Result<()> {
     while let Some(instance) = suite.next() {
         let evaluator = AcceleratedEvaluator::new(&CONTEXT, &mut suite, &instance);
 
-        let bounds = instance.domain();
-        let lower = bounds[0].start.clone();
-        let upper = bounds[0].end.clone();
-        let v_max = (upper - lower) / 2.0;
+        let v_max = swarm.v_max(&instance.domain());
 
-        let condition = if exploration == "evaluations" {
-            let eval_interval = exp_param * evaluations as f64;
-            conditions::StagnationForN::new(eval_interval as usize, ValueOf::<Evaluations>::new(), BestObjectiveValueLens::new(), PartialEqChecker::new())
-        } else {
-            conditions::LessThanN::new(exp_param, NormalizedDiversityLens::<MinimumIndividualDistance>::new())
-        };
+        let condition = exploration.condition(evaluations);
 
         // This is the main setup of the algorithm
-        let conf: Configuration<Instance> = random_restart_pso(
+        let conf: Configuration<Instance> = exploration_pso(
             evaluations,
             pop_size,
-            inertia_weight, // Weight
-            c1, // C1
-            c2, // C2
+            update_rule.rule(inertia_weight, c1, c2)?, // inertia weight and acceleration coefficients
             v_max,
+            swarm.topology, // neighbourhood topology
             condition, // exploration mechanism condition, i.e. when to randomly restart
+            ExplorationMechanism::RandomRestart(restart),
+            None,
+            exploration.attribution_window, // iterations in which a new global best counts as success
+            ParticlePolicy::default(), // no particles are replaced
+            boundary, // handling of solutions outside the domain
+            initialization, // initial population and re-initialisation on restarts
+            &[], // no additional diversity measures
         );
 
         // This executes the algorithm
@@ -15,6 +15,7 @@ use std::time::Instant;
 use once_cell::sync::Lazy;
 use clap::Parser;
 use crate::algorithms::shade::shade;
+use crate::algorithms::parameters::{BoundaryArgs, Crossover, InitializationArgs};
 
 static CONTEXT: Lazy<Context<C>> = Lazy::new(Context::default);
 
@@ -51,12 +52,18 @@ struct Args {
     y: u32,
     
     /// Crossover operator; bin or exp
-    #[arg(long, default_value = "bin")]
-    crossover: String,
+    #[arg(long, value_enum, default_value_t = Crossover::Bin)]
+    crossover: Crossover,
     
     /// History size, 1 to maximum number of iterations; tuning in 1 to 1000
     #[arg(long, default_value_t = 100)]
     history: usize,
+
+    #[command(flatten)]
+    boundary: BoundaryArgs,
+
+    #[command(flatten)]
+    initialization: InitializationArgs,
 }
 
 
@@ -72,6 +79,8 @@ fn main() -> anyhow::Result<()> {
     let y = args.y;
     let cr_operator = args.crossover;
     let history = args.history;
+    let boundary = args.boundary.boundary;
+    let initialization = args.initialization.initialization;
     
     // define remaining algorithmic parameters according to Tanabe and Fukunaga 2013
     let max_archive = pop_size as usize;
@@ -94,11 +103,7 @@ fn main() -> anyhow::Result<()> {
     while let Some(instance) = suite.next() {
         let evaluator = AcceleratedEvaluator::new(&CONTEXT, &mut suite, &instance);
         
-        let crossover = if cr_operator == "exp" {
-            recombination::de::DEExponentialCrossover::new(cr).unwrap()
-        } else {
-            recombination::de::DEBinomialCrossover::new(cr).unwrap()
-        };
+        let crossover = cr_operator.operator(cr);
         
         // This is the main setup of the algorithm
         let conf: Configuration<Instance> = shade(
@@ -110,6 +115,9 @@ fn main() -> anyhow::Result<()> {
             history, // maximum length of history for F and CR adaptation
             f, // initial value of F; of no consequence when using SHADEAdaptation
             crossover, // exp or bin
+            boundary, // handling of solutions outside the domain
+            initialization, // initial population
+            &[], // no additional diversity measures
         );
 
         // This executes the algorithm